@@ -0,0 +1,21 @@
+use std::time::Duration;
+
+use xosd_rs::{Color, Command, HorizontalAlign, Result, VerticalAlign, Xosd};
+
+fn main() -> Result<()> {
+    let mut osd = Xosd::new(1)?;
+
+    osd.set_color("White")?;
+    osd.set_vertical_align(VerticalAlign::Center)?;
+    osd.set_horizontal_align(HorizontalAlign::Center)?;
+    osd.set_fade_background(Color::rgb(0, 0, 0));
+
+    osd.display_with_fade(
+        0,
+        Command::string("Goodbye!")?,
+        Duration::from_secs(2),
+        Duration::from_secs(1),
+    )?;
+
+    Ok(())
+}
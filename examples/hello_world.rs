@@ -7,12 +7,8 @@ fn main() -> Result<()> {
     osd.set_vertical_align(VerticalAlign::Center)?;
     osd.set_horizontal_align(HorizontalAlign::Center)?;
 
-    osd.display(0, Command::string("Hello,")?)?;
-    osd.display(1, Command::string("World!")?)?;
-
-    if osd.onscreen()? {
-        osd.wait_until_no_display()?;
-    }
+    osd.display_string(0, "Hello,")?;
+    osd.display_blocking(1, Command::string("World!")?)?;
 
     Ok(())
 }
@@ -0,0 +1,241 @@
+use crate::{checked_cstring, Error, HorizontalAlign, Result, Snapshot, VerticalAlign, Xosd};
+
+/// Builds a configured [`Xosd`] object
+///
+/// The builder only stores the settings. The XOSD window is created and
+/// configured by [`XosdBuilder::build`]. Settings that are not set keep the
+/// xosd default.
+///
+/// # Example
+///
+/// ```
+/// # use xosd_rs::{Command, VerticalAlign, XosdBuilder};
+/// let mut osd = XosdBuilder::new()
+///     .lines(2)
+///     .font("fixed")
+///     .color("LawnGreen")
+///     .timeout(3)
+///     .shadow_offset(1)
+///     .vertical_align(VerticalAlign::Bottom)
+///     .build()?;
+///
+/// osd.display(0, Command::string("Example XOSD output")?)?;
+///
+/// # Ok::<(), xosd_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct XosdBuilder {
+    lines: i32,
+    state: Snapshot,
+}
+
+impl Default for XosdBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XosdBuilder {
+    /// Create a new [`XosdBuilder`] for a XOSD window with 1 line
+    pub fn new() -> Self {
+        Self {
+            lines: 1,
+            state: Snapshot::new(0),
+        }
+    }
+
+    /// Set the maximum number of lines, see [`Xosd::new`]
+    pub fn lines(mut self, lines: i32) -> Self {
+        self.lines = lines;
+        self
+    }
+
+    /// Set the text font, see [`Xosd::set_font`]
+    pub fn font<S>(mut self, font: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.state.font = Some(font.into());
+        self
+    }
+
+    /// Set the text color, see [`Xosd::set_color`]
+    pub fn color<S>(mut self, color: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.state.color = Some(color.into());
+        self
+    }
+
+    /// Set the shadow color, see [`Xosd::set_shadow_color`]
+    pub fn shadow_color<S>(mut self, color: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.state.shadow_color = Some(color.into());
+        self
+    }
+
+    /// Set the outline color, see [`Xosd::set_outline_color`]
+    pub fn outline_color<S>(mut self, color: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.state.outline_color = Some(color.into());
+        self
+    }
+
+    /// Set the timeout in seconds, see [`Xosd::set_timeout`]
+    pub fn timeout(mut self, timeout: u16) -> Self {
+        self.state.timeout = timeout.into();
+        self
+    }
+
+    /// Set the length of the percentage bar or slider, see
+    /// [`Xosd::set_bar_length`]
+    pub fn bar_length(mut self, percentage: Option<u16>) -> Self {
+        self.state.bar_length = percentage;
+        self
+    }
+
+    /// Set the vertical alignment, see [`Xosd::set_vertical_align`]
+    pub fn vertical_align(mut self, align: VerticalAlign) -> Self {
+        self.state.vertical_align = align;
+        self
+    }
+
+    /// Set the horizontal alignment, see [`Xosd::set_horizontal_align`]
+    pub fn horizontal_align(mut self, align: HorizontalAlign) -> Self {
+        self.state.horizontal_align = align;
+        self
+    }
+
+    /// Set the vertical offset, see [`Xosd::set_vertical_offset`]
+    pub fn vertical_offset(mut self, offset: i32) -> Self {
+        self.state.vertical_offset = offset;
+        self
+    }
+
+    /// Set the horizontal offset, see [`Xosd::set_horizontal_offset`]
+    pub fn horizontal_offset(mut self, offset: i32) -> Self {
+        self.state.horizontal_offset = offset;
+        self
+    }
+
+    /// Set the shadow offset, see [`Xosd::set_shadow_offset`]
+    pub fn shadow_offset(mut self, offset: i32) -> Self {
+        self.state.shadow_offset = offset;
+        self
+    }
+
+    /// Set the outline offset, see [`Xosd::set_outline_offset`]
+    pub fn outline_offset(mut self, offset: i32) -> Self {
+        self.state.outline_offset = offset;
+        self
+    }
+
+    /// Create the XOSD window and apply every setting
+    ///
+    /// # Errors
+    ///
+    /// These errors are returned before the XOSD window is created:
+    ///
+    /// * If `lines` is less than 1 [`Error::InvalidLineCount`] is returned.
+    /// * If a color contains control characters [`Error::InvalidColor`] is
+    ///   returned.
+    /// * If the font contains control characters [`Error::InvalidFont`] is
+    ///   returned.
+    /// * If the bar length is greater than 100 [`Error::OutOfRangePercentage`]
+    ///   is returned.
+    ///
+    /// If any of the underlying xosd functions fails the xosd error message is
+    /// wrapped in a [`Error::XosdError`] and returned.
+    pub fn build(self) -> Result<Xosd> {
+        if self.lines < 1 {
+            return Err(Error::InvalidLineCount);
+        }
+
+        let state = &self.state;
+        for color in [&state.color, &state.shadow_color, &state.outline_color]
+            .iter()
+            .copied()
+            .flatten()
+        {
+            checked_cstring(color.as_str(), "color", Error::InvalidColor)?;
+        }
+        if let Some(font) = &state.font {
+            checked_cstring(font.as_str(), "font", Error::InvalidFont)?;
+        }
+        if state.bar_length.is_some_and(|percentage| percentage > 100) {
+            return Err(Error::OutOfRangePercentage);
+        }
+
+        let mut osd = Xosd::new(self.lines)?;
+        osd.apply_state(state)?;
+
+        Ok(osd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        let mut osd = XosdBuilder::new()
+            .lines(2)
+            .font("fixed")
+            .color("LimeGreen")
+            .timeout(3)
+            .bar_length(Some(40))
+            .shadow_offset(1)
+            .vertical_align(VerticalAlign::Bottom)
+            .horizontal_align(HorizontalAlign::Center)
+            .build()
+            .unwrap();
+
+        assert_eq!(osd.max_lines(), Ok(2));
+        assert_eq!(osd.color(), Ok((50, 205, 50)));
+
+        let snapshot = osd.snapshot();
+        assert_eq!(snapshot.font.as_deref(), Some("fixed"));
+        assert_eq!(snapshot.timeout, 3);
+        assert_eq!(snapshot.bar_length, Some(40));
+        assert_eq!(snapshot.shadow_offset, 1);
+        assert_eq!(snapshot.vertical_align, VerticalAlign::Bottom);
+        assert_eq!(snapshot.horizontal_align, HorizontalAlign::Center);
+    }
+
+    #[test]
+    fn test_build_defaults() {
+        let mut osd = XosdBuilder::default().build().unwrap();
+
+        assert_eq!(osd.max_lines(), Ok(1));
+        assert_eq!(osd.snapshot().color, None);
+    }
+
+    #[test]
+    fn test_build_invalid() {
+        assert_eq!(
+            XosdBuilder::new().lines(0).build().unwrap_err(),
+            Error::InvalidLineCount
+        );
+        assert_eq!(
+            XosdBuilder::new().color("Lime\nGreen").build().unwrap_err(),
+            Error::InvalidColor("Lime\nGreen".to_string())
+        );
+        assert_eq!(
+            XosdBuilder::new().font("fixed\x07").build().unwrap_err(),
+            Error::InvalidFont("fixed\x07".to_string())
+        );
+        assert_eq!(
+            XosdBuilder::new()
+                .bar_length(Some(101))
+                .build()
+                .unwrap_err(),
+            Error::OutOfRangePercentage
+        );
+    }
+}
@@ -0,0 +1,585 @@
+use std::fmt;
+
+use crate::{Error, Result};
+
+/// The X11 color names and their RGB8 values
+const RGB_TXT: &str = include_str!("rgb.txt");
+
+/// The color spaces colors can be interpolated in
+///
+/// Interpolating in [`ColorSpace::Srgb`] is cheap but the middle of a fade looks
+/// darker than expected. [`ColorSpace::Linear`] interpolates in linear light
+/// which looks more even.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ColorSpace {
+    #[default]
+    Srgb,
+    Linear,
+}
+
+/// Represents a RGB8 color
+///
+/// This is the color returned by the XOSD library after converting it to RGB8.
+/// See [`Xosd::color`](crate::Xosd::color).
+///
+/// Every color setter like [`Xosd::set_color`](crate::Xosd::set_color)
+/// accepts a [`Color`] and passes it to X11 as `#rrggbb`.
+///
+/// # Example
+///
+/// ```
+/// # use xosd_rs::{Color, Xosd};
+/// let mut osd = Xosd::new(1)?;
+///
+/// osd.set_color(Color::rgb(50, 205, 50))?;
+/// osd.set_shadow_color(Color::hex("#1e1e1e")?)?;
+/// osd.set_outline_color(Color::name("Grey")?)?;
+///
+/// # Ok::<(), xosd_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Color {
+    /// Construct a [`Color`] from its red, green and blue channels
+    pub const fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+
+    /// Parse a hex color like `#32cd32` or `#3c3`
+    ///
+    /// The leading `#` is optional. In the short form every digit is
+    /// repeated, so `#3c3` is `#33cc33`.
+    ///
+    /// # Errors
+    ///
+    /// If `hex` is not a hex color [`Error::InvalidColor`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Color;
+    /// assert_eq!(Color::hex("#32cd32")?, Color::rgb(50, 205, 50));
+    /// assert_eq!(Color::hex("3c3")?, Color::rgb(0x33, 0xcc, 0x33));
+    /// assert!(Color::hex("#32cd3").is_err());
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn hex(hex: &str) -> Result<Self> {
+        let invalid = || Error::InvalidColor(hex.to_string());
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let channel = |index: usize, width: usize| -> Result<u8> {
+            let value = u8::from_str_radix(&digits[index * width..(index + 1) * width], 16)
+                .map_err(|_| invalid())?;
+            Ok(match width {
+                1 => value * 0x11,
+                _ => value,
+            })
+        };
+
+        match digits.len() {
+            3 | 6 => {
+                let width = digits.len() / 3;
+                Ok(Self::rgb(
+                    channel(0, width)?,
+                    channel(1, width)?,
+                    channel(2, width)?,
+                ))
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Look up a X11 color name like `LimeGreen`
+    ///
+    /// The names are taken from a copy of X11's `rgb.txt` and are matched
+    /// ignoring case.
+    ///
+    /// # Errors
+    ///
+    /// If `name` is not in `rgb.txt` [`Error::InvalidColor`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Color;
+    /// assert_eq!(Color::name("LimeGreen")?, Color::rgb(50, 205, 50));
+    /// assert_eq!(Color::name("lime green")?, Color::rgb(50, 205, 50));
+    /// assert!(Color::name("#32cd32").is_err());
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn name(name: &str) -> Result<Self> {
+        match name.starts_with('#') {
+            false => parse_x11_color(name),
+            true => None,
+        }
+        .map(|rgb| rgb16_to_rgb8(rgb).into())
+        .ok_or_else(|| Error::InvalidColor(name.to_string()))
+    }
+
+    /// Linearly interpolate between `self` and `other`
+    ///
+    /// `t` is clamped to `0.0..=1.0` where `0.0` returns `self` and `1.0`
+    /// returns `other`. The channels are interpolated in sRGB. Use
+    /// [`Color::interpolate`] to choose the color space.
+    pub fn lerp(&self, other: &Color, t: f32) -> Color {
+        self.interpolate(other, t, ColorSpace::Srgb)
+    }
+
+    /// Interpolate between `self` and `other` in `space`
+    ///
+    /// `t` is clamped to `0.0..=1.0` where `0.0` returns `self` and `1.0`
+    /// returns `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Color, ColorSpace};
+    /// let black = Color::rgb(0, 0, 0);
+    /// let white = Color::rgb(255, 255, 255);
+    ///
+    /// assert_eq!(black.interpolate(&white, 0.5, ColorSpace::Srgb), Color::rgb(128, 128, 128));
+    /// assert_eq!(black.interpolate(&white, 0.5, ColorSpace::Linear), Color::rgb(188, 188, 188));
+    /// ```
+    pub fn interpolate(&self, other: &Color, t: f32, space: ColorSpace) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |from: f32, to: f32| from + (to - from) * t;
+
+        match space {
+            ColorSpace::Srgb => {
+                let channel = |from: u8, to: u8| lerp(from as f32, to as f32).round() as u8;
+
+                Color::rgb(
+                    channel(self.red, other.red),
+                    channel(self.green, other.green),
+                    channel(self.blue, other.blue),
+                )
+            }
+            ColorSpace::Linear => {
+                let [r1, g1, b1] = self.to_linear();
+                let [r2, g2, b2] = other.to_linear();
+
+                Color::from_linear([lerp(r1, r2), lerp(g1, g2), lerp(b1, b2)])
+            }
+        }
+    }
+
+    /// Convert the color to linear light
+    ///
+    /// Returns the red, green and blue channels in `0.0..=1.0`.
+    pub fn to_linear(&self) -> [f32; 3] {
+        fn channel(value: u8) -> f32 {
+            let value = value as f32 / 255.0;
+
+            if value <= 0.04045 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        }
+
+        [channel(self.red), channel(self.green), channel(self.blue)]
+    }
+
+    /// Construct a [`Color`] from linear light
+    ///
+    /// This is the inverse of [`Color::to_linear`]. Channels are clamped to
+    /// `0.0..=1.0`.
+    pub fn from_linear([red, green, blue]: [f32; 3]) -> Self {
+        fn channel(value: f32) -> u8 {
+            let value = value.clamp(0.0, 1.0);
+            let value = if value <= 0.003_130_8 {
+                value * 12.92
+            } else {
+                1.055 * value.powf(1.0 / 2.4) - 0.055
+            };
+
+            (value * 255.0).round() as u8
+        }
+
+        Self::rgb(channel(red), channel(green), channel(blue))
+    }
+
+    /// Compare two colors allowing every channel to differ by `tolerance`
+    ///
+    /// Converting a RGB16 color to RGB8 involves rounding, so a color read
+    /// back from the XOSD library can be off by one from the color that was
+    /// set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Color;
+    /// let lime_green = Color::rgb(50, 205, 50);
+    ///
+    /// assert!(lime_green.approx_eq(&Color::rgb(51, 204, 50), 1));
+    /// assert!(!lime_green.approx_eq(&Color::rgb(52, 205, 50), 1));
+    /// ```
+    pub fn approx_eq(&self, other: &Color, tolerance: u8) -> bool {
+        self.red.abs_diff(other.red) <= tolerance
+            && self.green.abs_diff(other.green) <= tolerance
+            && self.blue.abs_diff(other.blue) <= tolerance
+    }
+}
+
+/// Formats the color as `#rrggbb` which is understood by X11
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+
+/// Returns whether `name` is a color the xosd color setters understand.
+///
+/// The color is resolved against a bundled copy of X11's `rgb.txt`, ignoring
+/// case, or parsed as a hex color like `#32cd32`. No X server is needed. A
+/// X server with a custom color database may know more colors.
+///
+/// # Example
+///
+/// ```
+/// # use xosd_rs::is_valid_color;
+/// assert!(is_valid_color("green"));
+/// assert!(is_valid_color("#32cd32"));
+/// assert!(!is_valid_color("notacolor"));
+/// ```
+pub fn is_valid_color(name: &str) -> bool {
+    parse_x11_color(name).is_some()
+}
+
+/// Formats a RGB8 color as `#rrggbb` for the xosd color setters.
+///
+/// This is the same as `Color::rgb(red, green, blue).to_string()`. The tuple
+/// returned by [`Xosd::color`](crate::Xosd::color) can also be converted into
+/// a [`Color`] and passed to the setters directly.
+///
+/// # Example
+///
+/// ```
+/// # use xosd_rs::{rgb_to_x11, Xosd};
+/// let mut osd = Xosd::new(1)?;
+///
+/// assert_eq!(rgb_to_x11(50, 205, 50), "#32cd32");
+///
+/// osd.set_color(rgb_to_x11(50, 205, 50))?;
+/// let (red, green, blue) = osd.color()?;
+/// osd.set_shadow_color(rgb_to_x11(red / 2, green / 2, blue / 2))?;
+///
+/// # Ok::<(), xosd_rs::Error>(())
+/// ```
+pub fn rgb_to_x11(red: u8, green: u8, blue: u8) -> String {
+    Color::rgb(red, green, blue).to_string()
+}
+
+/// Returns the colors of a fade from `from` to `to` in `steps` steps.
+///
+/// The first color is one step away from `from` and the last color is `to`.
+pub(crate) fn fade_steps(from: Color, to: Color, steps: u32, space: ColorSpace) -> Vec<Color> {
+    (1..=steps)
+        .map(|step| from.interpolate(&to, step as f32 / steps as f32, space))
+        .collect()
+}
+
+/// Converts a RGB16 color as returned by xosd to RGB8.
+///
+/// Every channel is scaled from `0..=65535` to `0..=255` and rounded, which
+/// turns the RGB16 values X11 uses for RGB8 colors (`value * 257`) back into
+/// the RGB8 values.
+pub(crate) fn rgb16_to_rgb8((red, green, blue): (u16, u16, u16)) -> (u8, u8, u8) {
+    let channel = |value: u16| ((u32::from(value) + 128) / 257) as u8;
+
+    (channel(red), channel(green), channel(blue))
+}
+
+/// Parses a color specification as RGB16 like X11 does.
+///
+/// Understands the color names from `rgb.txt` ignoring case and hex colors
+/// with 1 to 4 digits per channel like `#32cd32`. Like X11, hex channels
+/// with less than 4 digits are padded with zeros, so `#fff` is `0xf000`.
+pub(crate) fn parse_x11_color(spec: &str) -> Option<(u16, u16, u16)> {
+    if let Some(hex) = spec.strip_prefix('#') {
+        let digits = hex.len() / 3;
+        let valid = hex.bytes().all(|b| b.is_ascii_hexdigit());
+        if !valid || !(1..=4).contains(&digits) || hex.len() % 3 != 0 {
+            return None;
+        }
+
+        let channel = |index: usize| {
+            let value = hex.get(index * digits..(index + 1) * digits)?;
+            let value = u16::from_str_radix(value, 16).ok()?;
+            Some(value << (16 - 4 * digits))
+        };
+
+        return Some((channel(0)?, channel(1)?, channel(2)?));
+    }
+
+    RGB_TXT
+        .lines()
+        .filter(|line| !line.starts_with('!'))
+        .find_map(|line| {
+            let mut fields = line.split_whitespace();
+            let red: u16 = fields.next()?.parse().ok()?;
+            let green: u16 = fields.next()?.parse().ok()?;
+            let blue: u16 = fields.next()?.parse().ok()?;
+            let name = fields.collect::<Vec<_>>().join(" ");
+
+            match name.eq_ignore_ascii_case(spec) {
+                true => Some((red * 257, green * 257, blue * 257)),
+                false => None,
+            }
+        })
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from((red, green, blue): (u8, u8, u8)) -> Self {
+        Self::rgb(red, green, blue)
+    }
+}
+
+/// Formats the color as `#rrggbb` for the color setters of
+/// [`Xosd`](crate::Xosd)
+impl From<Color> for Vec<u8> {
+    fn from(color: Color) -> Self {
+        color.to_string().into_bytes()
+    }
+}
+
+impl From<Color> for (u8, u8, u8) {
+    fn from(color: Color) -> Self {
+        (color.red, color.green, color.blue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approx_eq_equal() {
+        let color = Color::rgb(50, 205, 50);
+
+        assert!(color.approx_eq(&color, 0));
+        assert!(color.approx_eq(&color, 255));
+    }
+
+    #[test]
+    fn test_approx_eq_within_tolerance() {
+        assert!(Color::rgb(50, 205, 50).approx_eq(&Color::rgb(49, 206, 51), 1));
+        assert!(Color::rgb(0, 0, 0).approx_eq(&Color::rgb(3, 0, 2), 3));
+    }
+
+    #[test]
+    fn test_approx_eq_out_of_tolerance() {
+        assert!(!Color::rgb(50, 205, 50).approx_eq(&Color::rgb(49, 206, 51), 0));
+        assert!(!Color::rgb(0, 0, 0).approx_eq(&Color::rgb(0, 0, 255), 254));
+    }
+
+    #[test]
+    fn test_lerp() {
+        let black = Color::rgb(0, 0, 0);
+        let white = Color::rgb(255, 255, 255);
+
+        assert_eq!(black.lerp(&white, 0.0), black);
+        assert_eq!(black.lerp(&white, 0.5), Color::rgb(128, 128, 128));
+        assert_eq!(black.lerp(&white, 1.0), white);
+        assert_eq!(black.lerp(&white, 2.0), white);
+    }
+
+    #[test]
+    fn test_fade_steps() {
+        assert_eq!(
+            fade_steps(
+                Color::rgb(0, 200, 100),
+                Color::rgb(0, 0, 0),
+                4,
+                ColorSpace::Srgb
+            ),
+            [
+                Color::rgb(0, 150, 75),
+                Color::rgb(0, 100, 50),
+                Color::rgb(0, 50, 25),
+                Color::rgb(0, 0, 0),
+            ]
+        );
+        assert_eq!(
+            fade_steps(
+                Color::rgb(10, 10, 10),
+                Color::rgb(20, 20, 20),
+                1,
+                ColorSpace::Linear
+            ),
+            [Color::rgb(20, 20, 20)]
+        );
+        assert!(fade_steps(
+            Color::rgb(10, 10, 10),
+            Color::rgb(20, 20, 20),
+            0,
+            ColorSpace::Srgb
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_interpolate_midpoint() {
+        let black = Color::rgb(0, 0, 0);
+        let white = Color::rgb(255, 255, 255);
+        let red = Color::rgb(255, 0, 0);
+        let blue = Color::rgb(0, 0, 255);
+
+        assert_eq!(
+            black.interpolate(&white, 0.5, ColorSpace::Srgb),
+            Color::rgb(128, 128, 128)
+        );
+        assert_eq!(
+            black.interpolate(&white, 0.5, ColorSpace::Linear),
+            Color::rgb(188, 188, 188)
+        );
+        assert_eq!(
+            red.interpolate(&blue, 0.5, ColorSpace::Srgb),
+            Color::rgb(128, 0, 128)
+        );
+        assert_eq!(
+            red.interpolate(&blue, 0.5, ColorSpace::Linear),
+            Color::rgb(188, 0, 188)
+        );
+    }
+
+    #[test]
+    fn test_linear_round_trip() {
+        for value in 0..=255 {
+            let color = Color::rgb(value, value, value);
+
+            assert_eq!(Color::from_linear(color.to_linear()), color);
+        }
+    }
+
+    #[test]
+    fn test_rgb16_to_rgb8() {
+        let cases = [
+            ((0, 0, 0), (0, 0, 0)),
+            ((65535, 65535, 65535), (255, 255, 255)),
+            ((32896, 32896, 32896), (128, 128, 128)),
+            ((12850, 52685, 12850), (50, 205, 50)),
+            ((0x8000, 0x7fff, 0x0081), (128, 127, 1)),
+        ];
+
+        for (rgb16, rgb8) in cases.iter() {
+            assert_eq!(rgb16_to_rgb8(*rgb16), *rgb8, "{:?}", rgb16);
+        }
+    }
+
+    #[test]
+    fn test_rgb16_to_rgb8_round_trip() {
+        for value in 0..=255u16 {
+            let channel = value * 257;
+
+            assert_eq!(
+                rgb16_to_rgb8((channel, channel, channel)),
+                (value as u8, value as u8, value as u8)
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_x11_color_name() {
+        assert_eq!(parse_x11_color("black"), Some((0, 0, 0)));
+        assert_eq!(parse_x11_color("White"), Some((65535, 65535, 65535)));
+        assert_eq!(parse_x11_color("LimeGreen"), Some((12850, 52685, 12850)));
+        assert_eq!(parse_x11_color("lime green"), Some((12850, 52685, 12850)));
+        assert_eq!(parse_x11_color("NoSuchColor"), None);
+        assert_eq!(parse_x11_color(""), None);
+    }
+
+    #[test]
+    fn test_parse_x11_color_hex() {
+        assert_eq!(parse_x11_color("#fff"), Some((0xf000, 0xf000, 0xf000)));
+        assert_eq!(parse_x11_color("#32cd32"), Some((0x3200, 0xcd00, 0x3200)));
+        assert_eq!(
+            parse_x11_color("#123456789"),
+            Some((0x1230, 0x4560, 0x7890))
+        );
+        assert_eq!(
+            parse_x11_color("#00007fffffff"),
+            Some((0x0000, 0x7fff, 0xffff))
+        );
+        assert_eq!(parse_x11_color("#12345"), None);
+        assert_eq!(parse_x11_color("#"), None);
+        assert_eq!(parse_x11_color("#ggg"), None);
+        assert_eq!(parse_x11_color("#+f+f+f"), None);
+        assert_eq!(parse_x11_color("#0123456789abc"), None);
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(Color::hex("#32cd32"), Ok(Color::rgb(50, 205, 50)));
+        assert_eq!(Color::hex("32CD32"), Ok(Color::rgb(50, 205, 50)));
+        assert_eq!(Color::hex("#fff"), Ok(Color::rgb(255, 255, 255)));
+        assert_eq!(Color::hex("#000000"), Ok(Color::rgb(0, 0, 0)));
+    }
+
+    #[test]
+    fn test_hex_invalid() {
+        for hex in &[
+            "",
+            "#",
+            "#ff",
+            "#ffff",
+            "#32cd3g",
+            "#+1+2+3",
+            "#32cd32ff",
+            "#ä1",
+        ] {
+            assert_eq!(Color::hex(hex), Err(Error::InvalidColor(hex.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(Color::name("White"), Ok(Color::rgb(255, 255, 255)));
+        assert_eq!(Color::name("LIMEGREEN"), Ok(Color::rgb(50, 205, 50)));
+        assert_eq!(
+            Color::name("NoSuchColor"),
+            Err(Error::InvalidColor("NoSuchColor".to_string()))
+        );
+        assert_eq!(
+            Color::name("#fff"),
+            Err(Error::InvalidColor("#fff".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_is_valid_color() {
+        assert!(is_valid_color("green"));
+        assert!(is_valid_color("Lime Green"));
+        assert!(is_valid_color("#fff"));
+        assert!(!is_valid_color("notacolor"));
+        assert!(!is_valid_color("#ff"));
+    }
+
+    #[test]
+    fn test_rgb_to_x11() {
+        assert_eq!(rgb_to_x11(0, 0, 0), "#000000");
+        assert_eq!(rgb_to_x11(50, 205, 50), "#32cd32");
+        assert_eq!(rgb_to_x11(255, 10, 171), "#ff0aab");
+    }
+
+    #[test]
+    fn test_into_bytes() {
+        assert_eq!(Vec::from(Color::rgb(50, 205, 50)), b"#32cd32");
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Color::rgb(50, 205, 50).to_string(), "#32cd32");
+    }
+}
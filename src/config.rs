@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, HorizontalAlign, Result, Snapshot, VerticalAlign};
+
+/// The most lines a config may ask for
+///
+/// More lines than this don't fit on any screen. Without a limit a config like
+/// `lines = 2000000000` would allocate the cache for every line before the
+/// window is created.
+pub(crate) const MAX_LINES: usize = 1024;
+
+/// The settings of a [`Snapshot`] as written to a config
+///
+/// The content of the lines is not exported, only the number of lines.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    lines: usize,
+    timeout: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bar_length: Option<u16>,
+    vertical_offset: i32,
+    horizontal_offset: i32,
+    shadow_offset: i32,
+    outline_offset: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    font: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shadow_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outline_color: Option<String>,
+    vertical_align: VerticalAlign,
+    horizontal_align: HorizontalAlign,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::from(&Snapshot::new(1))
+    }
+}
+
+impl From<&Snapshot> for Config {
+    fn from(state: &Snapshot) -> Self {
+        Self {
+            lines: state.lines.len(),
+            timeout: state.timeout,
+            bar_length: state.bar_length,
+            vertical_offset: state.vertical_offset,
+            horizontal_offset: state.horizontal_offset,
+            shadow_offset: state.shadow_offset,
+            outline_offset: state.outline_offset,
+            color: state.color.clone(),
+            font: state.font.clone(),
+            shadow_color: state.shadow_color.clone(),
+            outline_color: state.outline_color.clone(),
+            vertical_align: state.vertical_align,
+            horizontal_align: state.horizontal_align,
+        }
+    }
+}
+
+/// Serializes the settings of `state` as TOML.
+pub(crate) fn export(state: &Snapshot) -> String {
+    // The config only has integers and strings, which TOML can always represent
+    toml::to_string(&Config::from(state)).unwrap()
+}
+
+/// Parses a config written by [`export`].
+///
+/// Missing settings keep their default. Without `lines` the window has 1
+/// line.
+pub(crate) fn parse(config: &str) -> Result<Snapshot> {
+    let config: Config =
+        toml::from_str(config).map_err(|error| Error::InvalidConfig(error.to_string()))?;
+
+    if config.lines > MAX_LINES {
+        return Err(Error::InvalidConfig(format!(
+            "expected at most {} lines, got {}",
+            MAX_LINES, config.lines
+        )));
+    }
+
+    let mut state = Snapshot::new(config.lines);
+    state.timeout = config.timeout;
+    state.bar_length = config.bar_length;
+    state.vertical_offset = config.vertical_offset;
+    state.horizontal_offset = config.horizontal_offset;
+    state.shadow_offset = config.shadow_offset;
+    state.outline_offset = config.outline_offset;
+    state.color = config.color;
+    state.font = config.font;
+    state.shadow_color = config.shadow_color;
+    state.outline_color = config.outline_color;
+    state.vertical_align = config.vertical_align;
+    state.horizontal_align = config.horizontal_align;
+
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export() {
+        let mut state = Snapshot::new(2);
+        state.color = Some("LimeGreen".to_string());
+        state.timeout = 3;
+        state.bar_length = Some(40);
+        state.vertical_align = VerticalAlign::Bottom;
+
+        assert_eq!(
+            export(&state),
+            "lines = 2\n\
+             timeout = 3\n\
+             bar_length = 40\n\
+             vertical_offset = 0\n\
+             horizontal_offset = 0\n\
+             shadow_offset = 0\n\
+             outline_offset = 0\n\
+             color = \"LimeGreen\"\n\
+             vertical_align = \"bottom\"\n\
+             horizontal_align = \"left\"\n"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut state = Snapshot::new(3);
+        state.color = Some("#32cd32".to_string());
+        state.font = Some(r#"-*-"quoted"-*"#.to_string());
+        state.shadow_color = Some("Black".to_string());
+        state.outline_color = Some("Grey".to_string());
+        state.timeout = -1;
+        state.vertical_align = VerticalAlign::Center;
+        state.horizontal_align = HorizontalAlign::Right;
+        state.vertical_offset = -20;
+        state.horizontal_offset = 10;
+        state.shadow_offset = 2;
+        state.outline_offset = 1;
+
+        assert_eq!(parse(&export(&state)), Ok(state));
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        assert_eq!(parse("# Nothing to see\n\n"), Ok(Snapshot::new(1)));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for config in &[
+            "lines = 2\ncolour = \"red\"",
+            "timeout = \"soon\"",
+            "color",
+            "vertical_align = \"up\"",
+            "lines = -1",
+        ] {
+            assert!(
+                matches!(parse(config), Err(Error::InvalidConfig(_))),
+                "{:?} was parsed",
+                config
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_too_many_lines() {
+        assert_eq!(
+            parse("lines = 2000000000"),
+            Err(Error::InvalidConfig(
+                "expected at most 1024 lines, got 2000000000".to_string()
+            ))
+        );
+        assert_eq!(
+            parse("lines = 1024").map(|state| state.lines.len()),
+            Ok(1024)
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{HorizontalAlign, Result, VerticalAlign, Xosd};
+
+/// Changes the settings of a [`Xosd`] in a chain
+///
+/// Returned by [`Xosd::configure`]. Every method calls the setter of the same
+/// name on the [`Xosd`] and returns the [`Configurator`] again, so the chain
+/// stops at the first error. Settings applied before the error are kept.
+///
+/// # Example
+///
+/// ```
+/// # use xosd_rs::{HorizontalAlign, Xosd};
+/// let mut osd = Xosd::new(1)?;
+///
+/// osd.configure()
+///     .font("fixed")?
+///     .color("green")?
+///     .timeout(3)?
+///     .horizontal_align(HorizontalAlign::Center)?;
+///
+/// # Ok::<(), xosd_rs::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Configurator<'a> {
+    osd: &'a mut Xosd,
+}
+
+impl<'a> Configurator<'a> {
+    pub(crate) fn new(osd: &'a mut Xosd) -> Self {
+        Self { osd }
+    }
+
+    /// Set the text font, see [`Xosd::set_font`]
+    pub fn font<S>(self, font: S) -> Result<Self>
+    where
+        S: Into<Vec<u8>>,
+    {
+        self.osd.set_font(font)?;
+        Ok(self)
+    }
+
+    /// Set the text color, see [`Xosd::set_color`]
+    pub fn color<S>(self, color: S) -> Result<Self>
+    where
+        S: Into<Vec<u8>>,
+    {
+        self.osd.set_color(color)?;
+        Ok(self)
+    }
+
+    /// Set the shadow color, see [`Xosd::set_shadow_color`]
+    pub fn shadow_color<S>(self, color: S) -> Result<Self>
+    where
+        S: Into<Vec<u8>>,
+    {
+        self.osd.set_shadow_color(color)?;
+        Ok(self)
+    }
+
+    /// Set the outline color, see [`Xosd::set_outline_color`]
+    pub fn outline_color<S>(self, color: S) -> Result<Self>
+    where
+        S: Into<Vec<u8>>,
+    {
+        self.osd.set_outline_color(color)?;
+        Ok(self)
+    }
+
+    /// Set the timeout in seconds, see [`Xosd::set_timeout`]
+    pub fn timeout(self, timeout: u16) -> Result<Self> {
+        self.osd.set_timeout(timeout)?;
+        Ok(self)
+    }
+
+    /// Set the length of the percentage bar or slider, see
+    /// [`Xosd::set_bar_length`]
+    pub fn bar_length(self, percentage: Option<u16>) -> Result<Self> {
+        self.osd.set_bar_length(percentage)?;
+        Ok(self)
+    }
+
+    /// Set the vertical alignment, see [`Xosd::set_vertical_align`]
+    pub fn vertical_align(self, align: VerticalAlign) -> Result<Self> {
+        self.osd.set_vertical_align(align)?;
+        Ok(self)
+    }
+
+    /// Set the horizontal alignment, see [`Xosd::set_horizontal_align`]
+    pub fn horizontal_align(self, align: HorizontalAlign) -> Result<Self> {
+        self.osd.set_horizontal_align(align)?;
+        Ok(self)
+    }
+
+    /// Set the vertical offset, see [`Xosd::set_vertical_offset`]
+    pub fn vertical_offset(self, offset: i32) -> Result<Self> {
+        self.osd.set_vertical_offset(offset)?;
+        Ok(self)
+    }
+
+    /// Set the horizontal offset, see [`Xosd::set_horizontal_offset`]
+    pub fn horizontal_offset(self, offset: i32) -> Result<Self> {
+        self.osd.set_horizontal_offset(offset)?;
+        Ok(self)
+    }
+
+    /// Set the shadow offset, see [`Xosd::set_shadow_offset`]
+    pub fn shadow_offset(self, offset: i32) -> Result<Self> {
+        self.osd.set_shadow_offset(offset)?;
+        Ok(self)
+    }
+
+    /// Set the outline offset, see [`Xosd::set_outline_offset`]
+    pub fn outline_offset(self, offset: i32) -> Result<Self> {
+        self.osd.set_outline_offset(offset)?;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Error;
+
+    use super::*;
+
+    #[test]
+    fn test_chain() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.configure()
+            .color("LimeGreen")
+            .and_then(|config| config.timeout(3))
+            .and_then(|config| config.vertical_align(VerticalAlign::Bottom))
+            .and_then(|config| config.shadow_offset(2))
+            .unwrap();
+
+        assert_eq!(osd.color(), Ok((50, 205, 50)));
+        let snapshot = osd.snapshot();
+        assert_eq!(snapshot.timeout, 3);
+        assert_eq!(snapshot.vertical_align, VerticalAlign::Bottom);
+        assert_eq!(snapshot.shadow_offset, 2);
+    }
+
+    #[test]
+    fn test_chain_error() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        let res = osd
+            .configure()
+            .timeout(3)
+            .and_then(|config| config.bar_length(Some(101)))
+            .and_then(|config| config.shadow_offset(2));
+
+        assert_eq!(res.unwrap_err(), Error::OutOfRangePercentage);
+        assert_eq!(osd.snapshot().timeout, 3);
+        assert_eq!(osd.shadow_offset(), 0);
+    }
+}
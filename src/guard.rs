@@ -0,0 +1,103 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::Xosd;
+
+/// Hides a XOSD window when dropped
+///
+/// Returned by [`Xosd::display_scoped`]. The guard borrows the [`Xosd`]
+/// mutably and dereferences to it, so the window can still be updated while
+/// the guard is alive.
+///
+/// If the window is still visible when the guard is dropped it is hidden. If
+/// hiding fails the error is logged as a warning with the `log` feature and
+/// printed to stderr without it instead of panicking.
+///
+/// # Example
+///
+/// ```
+/// # use xosd_rs::{Command, Xosd};
+/// let mut osd = Xosd::new(2)?;
+///
+/// {
+///     let mut guard = osd.display_scoped(0, Command::string("Indexing...")?)?;
+///     guard.display(1, Command::percentage(50)?)?;
+///
+///     assert!(guard.onscreen()?);
+/// }
+///
+/// assert!(!osd.onscreen()?);
+///
+/// # Ok::<(), xosd_rs::Error>(())
+/// ```
+#[derive(Debug)]
+#[must_use = "the window is hidden immediately if the guard is not used"]
+pub struct DisplayGuard<'a> {
+    osd: &'a mut Xosd,
+}
+
+impl<'a> DisplayGuard<'a> {
+    pub(crate) fn new(osd: &'a mut Xosd) -> Self {
+        Self { osd }
+    }
+}
+
+impl Deref for DisplayGuard<'_> {
+    type Target = Xosd;
+
+    fn deref(&self) -> &Xosd {
+        self.osd
+    }
+}
+
+impl DerefMut for DisplayGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Xosd {
+        self.osd
+    }
+}
+
+impl Drop for DisplayGuard<'_> {
+    fn drop(&mut self) {
+        let res = match self.osd.onscreen() {
+            Ok(true) => self.osd.hide(),
+            Ok(false) => Ok(()),
+            Err(error) => Err(error),
+        };
+
+        if let Err(error) = res {
+            warn!("Could not hide xosd window: {}", error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Command;
+
+    use super::*;
+
+    #[test]
+    fn test_hide_on_drop() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        let mut guard = osd
+            .display_scoped(0, Command::string("Busy").unwrap())
+            .unwrap();
+        assert_eq!(guard.onscreen(), Ok(true));
+
+        drop(guard);
+        assert_eq!(osd.onscreen(), Ok(false));
+    }
+
+    #[test]
+    fn test_already_hidden() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        let mut guard = osd
+            .display_scoped(0, Command::string("Busy").unwrap())
+            .unwrap();
+        guard.hide().unwrap();
+
+        drop(guard);
+        assert_eq!(osd.onscreen(), Ok(false));
+    }
+}
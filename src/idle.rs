@@ -0,0 +1,271 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    timer::{lock, Timer},
+    Command, Result, Xosd,
+};
+
+/// Hides a XOSD window once nothing was displayed for a while
+///
+/// Every update displayed through [`IdleHider::display`] (or announced with
+/// [`IdleHider::touch`]) restarts the idle time. [`IdleHider::tick`] hides the
+/// window once the idle time has passed since the most recent update. This
+/// debounces hiding for bursty updates like a volume that is changed in quick
+/// succession.
+///
+/// The [`IdleHider`] does not spawn a thread. Call [`IdleHider::tick`] from
+/// your own timer or event loop, possibly sleeping until
+/// [`IdleHider::deadline`], or use [`IdleTimer`] to hide the window in a
+/// background thread.
+///
+/// # Example
+///
+/// ```
+/// # use std::time::{Duration, Instant};
+/// # use xosd_rs::{Command, IdleHider, Xosd};
+/// let mut osd = Xosd::new(1)?;
+/// let mut hider = IdleHider::new(Duration::from_millis(50));
+///
+/// hider.display(&mut osd, 0, Command::percentage(40)?)?;
+/// hider.display(&mut osd, 0, Command::percentage(45)?)?;
+///
+/// while let Some(deadline) = hider.deadline() {
+///     std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+///     hider.tick(&mut osd)?;
+/// }
+///
+/// # Ok::<(), xosd_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct IdleHider {
+    idle: Duration,
+    last_update: Option<Instant>,
+}
+
+impl IdleHider {
+    /// Create a new [`IdleHider`] hiding the window after `idle`
+    pub fn new(idle: Duration) -> Self {
+        Self {
+            idle,
+            last_update: None,
+        }
+    }
+
+    /// Display data and restart the idle time
+    ///
+    /// # Errors
+    ///
+    /// See [`Xosd::display`].
+    pub fn display(&mut self, osd: &mut Xosd, line: i32, command: Command) -> Result<u16> {
+        let res = osd.display(line, command)?;
+        self.touch();
+        Ok(res)
+    }
+
+    /// Restart the idle time without displaying anything
+    ///
+    /// Use this if data was displayed without [`IdleHider::display`].
+    pub fn touch(&mut self) {
+        self.touch_at(Instant::now());
+    }
+
+    fn touch_at(&mut self, now: Instant) {
+        self.last_update = Some(now);
+    }
+
+    /// Returns when the window should be hidden
+    ///
+    /// Returns [`None`] if there was no update since the window was last
+    /// hidden.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.last_update.map(|last_update| last_update + self.idle)
+    }
+
+    /// Returns whether the window should be hidden at `now`
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.deadline().is_some_and(|deadline| now >= deadline)
+    }
+
+    /// Hide the window if the idle time has passed since the last update
+    ///
+    /// Returns whether the idle time has passed.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_is_onscreen` or `xosd_hide` fails the xosd error message is
+    ///   wrapped in a [`Error::XosdError`](crate::Error::XosdError) and
+    ///   returned.
+    pub fn tick(&mut self, osd: &mut Xosd) -> Result<bool> {
+        if !self.is_due(Instant::now()) {
+            return Ok(false);
+        }
+
+        self.last_update = None;
+        if osd.onscreen()? {
+            osd.hide()?;
+        }
+
+        Ok(true)
+    }
+}
+
+/// Hides a shared XOSD window in a background thread once nothing was
+/// displayed for a while
+///
+/// This drives an [`IdleHider`] with a background timer: every update
+/// displayed through [`IdleTimer::display`] (or announced with
+/// [`IdleTimer::touch`]) cancels the pending hide and reschedules it for the
+/// end of the new idle time. The thread locks the [`Xosd`] only while hiding
+/// it and is stopped when the [`IdleTimer`] is dropped.
+///
+/// If hiding fails the error is logged as a warning with the `log` feature and
+/// printed to stderr without it.
+///
+/// # Example
+///
+/// ```
+/// # use std::{sync::{Arc, Mutex}, thread, time::Duration};
+/// # use xosd_rs::{Command, IdleTimer, Xosd};
+/// let osd = Arc::new(Mutex::new(Xosd::new(1)?));
+/// let timer = IdleTimer::new(Arc::clone(&osd), Duration::from_millis(50));
+///
+/// timer.display(0, Command::percentage(40)?)?;
+/// timer.display(0, Command::percentage(45)?)?;
+///
+/// thread::sleep(Duration::from_millis(200));
+/// assert!(!osd.lock().unwrap().onscreen()?);
+///
+/// # Ok::<(), xosd_rs::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct IdleTimer {
+    osd: Arc<Mutex<Xosd>>,
+    hider: Arc<Mutex<IdleHider>>,
+    timer: Timer,
+}
+
+impl IdleTimer {
+    /// Start a background thread hiding `osd` after `idle`
+    pub fn new(osd: Arc<Mutex<Xosd>>, idle: Duration) -> Self {
+        let hider = Arc::new(Mutex::new(IdleHider::new(idle)));
+
+        let task_osd = Arc::clone(&osd);
+        let task_hider = Arc::clone(&hider);
+        let timer = Timer::spawn(move || {
+            let mut osd = lock(&task_osd);
+            let mut hider = lock(&task_hider);
+
+            match hider.tick(&mut osd) {
+                Ok(_) => hider.deadline(),
+                Err(error) => {
+                    warn!("Could not hide xosd window: {}", error);
+                    None
+                }
+            }
+        });
+
+        Self { osd, hider, timer }
+    }
+
+    /// Display data and reschedule hiding the window
+    ///
+    /// # Errors
+    ///
+    /// See [`Xosd::display`].
+    pub fn display(&self, line: i32, command: Command) -> Result<u16> {
+        let mut osd = lock(&self.osd);
+        let res = lock(&self.hider).display(&mut osd, line, command)?;
+        drop(osd);
+
+        self.timer.reschedule();
+        Ok(res)
+    }
+
+    /// Reschedule hiding the window without displaying anything
+    ///
+    /// Use this if data was displayed without [`IdleTimer::display`].
+    pub fn touch(&self) {
+        lock(&self.hider).touch();
+        self.timer.reschedule();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_not_due_without_update() {
+        let hider = IdleHider::new(Duration::from_secs(1));
+
+        assert_eq!(hider.deadline(), None);
+        assert!(!hider.is_due(Instant::now()));
+    }
+
+    #[test]
+    fn test_due_after_last_update() {
+        let start = Instant::now();
+        let idle = Duration::from_secs(2);
+        let mut hider = IdleHider::new(idle);
+
+        hider.touch_at(start);
+        assert!(!hider.is_due(start + Duration::from_secs(1)));
+
+        let last_update = start + Duration::from_secs(1);
+        hider.touch_at(last_update);
+        assert!(!hider.is_due(start + idle));
+        assert!(!hider.is_due(last_update + idle - Duration::from_millis(1)));
+        assert!(hider.is_due(last_update + idle));
+        assert_eq!(hider.deadline(), Some(last_update + idle));
+    }
+
+    #[test]
+    fn test_tick() {
+        let mut osd = Xosd::new(1).unwrap();
+        let mut hider = IdleHider::new(Duration::from_millis(10));
+
+        hider
+            .display(&mut osd, 0, Command::percentage(40).unwrap())
+            .unwrap();
+        assert_eq!(hider.tick(&mut osd), Ok(false));
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(hider.tick(&mut osd), Ok(true));
+        assert_eq!(osd.onscreen(), Ok(false));
+        assert_eq!(hider.tick(&mut osd), Ok(false));
+    }
+
+    #[test]
+    fn test_timer_reschedules() {
+        let osd = Arc::new(Mutex::new(Xosd::new(1).unwrap()));
+        let timer = IdleTimer::new(Arc::clone(&osd), Duration::from_millis(100));
+
+        timer.display(0, Command::percentage(40).unwrap()).unwrap();
+        std::thread::sleep(Duration::from_millis(60));
+        timer.display(0, Command::percentage(45).unwrap()).unwrap();
+
+        // 120ms after the first but only 60ms after the last update
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(lock(&osd).onscreen(), Ok(true));
+
+        std::thread::sleep(Duration::from_millis(150));
+        assert_eq!(lock(&osd).onscreen(), Ok(false));
+    }
+
+    #[test]
+    fn test_timer_touch() {
+        let osd = Arc::new(Mutex::new(Xosd::new(1).unwrap()));
+        let timer = IdleTimer::new(Arc::clone(&osd), Duration::from_millis(20));
+
+        lock(&osd).display_string(0, "Muted").unwrap();
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(lock(&osd).onscreen(), Ok(true));
+
+        timer.touch();
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(lock(&osd).onscreen(), Ok(false));
+    }
+}
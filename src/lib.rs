@@ -27,23 +27,142 @@
 //! ```
 //!
 //! More examples can be found [here](https://github.com/Chronophylos/xosd-rs/tree/main/examples).
+//!
+//! # Linking
+//!
+//! [`xosd_sys`] links the xosd shared library (`libxosd.so`) dynamically. If
+//! the library is missing at runtime the dynamic loader refuses to start the
+//! program before any Rust code runs, so this can't be reported as an
+//! [`Error`]. Make sure the library is installed wherever your program runs.
+//!
+//! # Testing without X
+//!
+//! With the `mock` feature every call into the xosd library is replaced by an
+//! in-memory mock, so [`Xosd::new`] succeeds without a X server. The mock
+//! stores the displayed text, parses colors like X11 and records every xosd
+//! function called. Use `Xosd::mock_line` and `Xosd::mock_operations` to
+//! inspect it in your tests. The xosd library is still needed to link the
+//! program.
+//!
+//! # Serde
+//!
+//! With the `serde` feature [`VerticalAlign`], [`HorizontalAlign`],
+//! [`Command`] and [`Snapshot`] implement `Serialize` and `Deserialize`. The variants use the
+//! lowercase names, e.g. `"center"` or `{ "percentage": 42 }`. Deserializing a
+//! [`Command`] does not check the value like [`Command::percentage`] does.
+//!
+//! The feature also adds [`Xosd::export_config`] and [`Xosd::from_config`] to
+//! save and restore the appearance of a window as TOML.
+//!
+//! # Logging
+//!
+//! With the `log` feature a debug record is emitted through the [`log`
+//! crate](https://docs.rs/log) for every xosd function called, with its
+//! result, and for the arguments of [`Xosd::display`] and the font, color and
+//! timeout setters. Errors that can't be returned, like a failing
+//! `xosd_uninit` when a [`Xosd`] is dropped, are logged as warnings. Without
+//! the feature nothing is logged and these errors are printed to stderr.
+//!
+//! # Async
+//!
+//! With the `tokio` feature `Xosd::wait_until_no_display_async` waits for
+//! the window to clear without blocking the thread.
+//!
+//! # X error handling
+//!
+//! xosd opens its own connection to the X server and does not install an X
+//! error handler, so a handler set by your application with `XSetErrorHandler`
+//! stays in place after [`Xosd::new`] and after dropping the [`Xosd`] object.
+//! Keep in mind that Xlib error handlers are global to the process: your
+//! handler is also called for errors on the connection used by xosd.
 #![doc(html_root_url = "https://docs.rs/xosd-rs/0.2.0")]
 
 use std::{
     borrow::Cow,
-    convert::TryInto,
+    cell::Cell,
+    convert::{TryFrom, TryInto},
+    env,
     ffi::{CStr, CString},
     fmt,
-    os::raw::c_uint,
+    hash::{Hash, Hasher},
+    iter,
+    num::NonZeroU32,
+    os::raw::{c_int, c_uint},
+    str::FromStr,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
 };
 
+#[cfg(feature = "mock")]
+use mock::*;
 use thiserror::Error;
+#[cfg(not(feature = "mock"))]
 use xosd_sys::*;
 
+/// Emits a debug record with the `log` feature and does nothing without it
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::debug!($($arg)*);
+    };
+}
+
+/// Emits a warning record with the `log` feature and prints to stderr without
+/// it
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::warn!($($arg)*);
+        #[cfg(not(feature = "log"))]
+        eprintln!($($arg)*);
+    };
+}
+
+mod builder;
+mod color;
+#[cfg(feature = "serde")]
+mod config;
+mod configure;
+mod guard;
+mod idle;
+mod line_timer;
+#[cfg(feature = "mock")]
+mod mock;
+#[cfg(feature = "palettes")]
+mod palette;
+mod snapshot;
+mod spinner;
+mod timer;
+mod xlfd;
+
+use color::{fade_steps, parse_x11_color, rgb16_to_rgb8};
+
+pub use builder::XosdBuilder;
+pub use color::{is_valid_color, rgb_to_x11, Color, ColorSpace};
+pub use configure::Configurator;
+pub use guard::DisplayGuard;
+pub use idle::{IdleHider, IdleTimer};
+pub use line_timer::LineTimer;
+#[cfg(feature = "palettes")]
+pub use palette::{Palette, Role};
+pub use snapshot::Snapshot;
+pub use spinner::Spinner;
+pub use xlfd::Xlfd;
+
+macro_rules! call_xosd {
+    ($fn:ident($($arg:expr),* $(,)?)) => {{
+        LAST_OPERATION.with(|operation| operation.set(stringify!($fn)));
+        let result = unsafe { $fn($($arg),*) };
+        debug!("{} returned {:?}", stringify!($fn), result);
+        result
+    }};
+}
+
 macro_rules! wrap_unsafe {
-    ($fn:expr) => {
-        if unsafe { $fn } != 0 {
-            Err(Error::XosdError(error_str()?.into_owned()))
+    ($fn:ident($($arg:expr),* $(,)?)) => {
+        if call_xosd!($fn($($arg),*)) != 0 {
+            Err(last_error())
         } else {
             Ok(())
         }
@@ -80,9 +199,56 @@ pub enum Error {
     #[error("Cannot create a xosd object with zero or less lines")]
     InvalidLineCount,
 
-    #[error("Percentage must be between 1 and 100")]
+    #[error("Percentage must be between 0 and 100")]
     OutOfRangePercentage,
 
+    /// Used when a command passed to [`Xosd::display_bar_colored`] is not a
+    /// percentage bar or slider
+    #[error("Expected a percentage bar or slider")]
+    NotABar,
+
+    /// Used when [`Xosd::new`] can't connect to a X display
+    #[error("No X display available, is DISPLAY set and the X server running?")]
+    NoDisplay,
+
+    /// Used when a color contains control characters
+    #[error("Invalid color: {0:?}")]
+    InvalidColor(String),
+
+    /// Used when a font contains control characters
+    #[error("Invalid font: {0:?}")]
+    InvalidFont(String),
+
+    /// Used when the X server can't load a font passed to
+    /// [`Xosd::set_font_strict`]
+    #[error("Font not found: {0:?}")]
+    FontNotFound(String),
+
+    /// Used when a line index is outside of the lines of a xosd object
+    #[error("Line {line} is out of range for a xosd object with {max} lines")]
+    InvalidLine { line: i32, max: i32 },
+
+    /// Used when more lines are given than a xosd object can display
+    #[error("Cannot display {lines} lines on a xosd object with {max} lines")]
+    TooManyLines { lines: usize, max: i32 },
+
+    /// Used when a string is not the name of a [`VerticalAlign`] or
+    /// [`HorizontalAlign`]
+    #[error("Invalid alignment: {0:?}")]
+    InvalidAlignment(String),
+
+    /// Used when a config passed to [`Xosd::from_config`] can't be parsed
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
+
+    /// Used when a string passed as `field` contains a NUL byte
+    #[error("Invalid {field}: NUL byte at position {}", .source.nul_position())]
+    InvalidString {
+        field: &'static str,
+        #[source]
+        source: std::ffi::NulError,
+    },
+
     /// Used when a [`std::ffi::NulError`] occurs
     #[error("Could not create CString from String")]
     CStringNullError(
@@ -102,10 +268,228 @@ pub enum Error {
 /// A helpful type to reduce repeated code
 pub type Result<T> = std::result::Result<T, Error>;
 
+thread_local! {
+    /// The name of the last xosd function called on this thread
+    static LAST_OPERATION: Cell<&'static str> = const { Cell::new("") };
+}
+
 fn error_str<'a>() -> Result<Cow<'a, str>> {
     wrap_static_string!(xosd_error)
 }
 
+/// Wrap the xosd error message in a [`Error::XosdError`].
+///
+/// Since `xosd_error` is global the message is prefixed with the last xosd
+/// function called on this thread, e.g. `[during set_font] ...`. If xosd did
+/// not set an error message `unknown xosd error` is used instead, so the
+/// failure is not hidden behind [`Error::IsNullPtr`].
+fn last_error() -> Error {
+    let message = error_str().unwrap_or(Cow::Borrowed("unknown xosd error"));
+
+    Error::XosdError(LAST_OPERATION.with(|operation| match operation.get() {
+        "" => message.into_owned(),
+        operation => format!(
+            "[during {}] {}",
+            operation.trim_start_matches("xosd_"),
+            message
+        ),
+    }))
+}
+
+/// Check whether a failed `xosd_create` was caused by a missing X display.
+///
+/// xosd opens the display named by `DISPLAY`, so an unset or empty `DISPLAY`
+/// always fails. Otherwise the xosd error message names the display if it
+/// could not be opened.
+fn display_unavailable() -> bool {
+    let unset = env::var_os("DISPLAY").is_none_or(|display| display.is_empty());
+
+    unset || matches!(error_str(), Ok(message) if message.to_ascii_lowercase().contains("display"))
+}
+
+/// Convert `value` into a [`CString`], naming `field` in the error if it
+/// contains a NUL byte.
+fn field_cstring<S>(value: S, field: &'static str) -> Result<CString>
+where
+    S: Into<Vec<u8>>,
+{
+    CString::new(value).map_err(|source| Error::InvalidString { field, source })
+}
+
+/// Convert `value` into a [`CString`] rejecting any control characters.
+///
+/// Interior NUL bytes result in [`Error::InvalidString`] naming `field`. Any
+/// other control character results in the error built by `error`.
+fn checked_cstring<S>(value: S, field: &'static str, error: fn(String) -> Error) -> Result<CString>
+where
+    S: Into<Vec<u8>>,
+{
+    let value = value.into();
+
+    if value.iter().any(|&b| b.is_ascii_control() && b != 0) {
+        return Err(error(String::from_utf8_lossy(&value).into_owned()));
+    }
+
+    field_cstring(value, field)
+}
+
+/// Convert the value returned by `xosd_display` into the value returned by
+/// [`Xosd::display`].
+fn display_result(res: c_int) -> Result<u16> {
+    if res < 0 {
+        Err(last_error())
+    } else {
+        Ok(res.try_into()?)
+    }
+}
+
+/// Returns the vertical offset that centers `lines` lines on the screen.
+///
+/// XOSD draws lines from the top of its window which is `max_lines` lines high,
+/// so a centered window with fewer lines would show them above the center.
+/// Negative offsets move a centered window down.
+fn block_center_offset(lines: i32, max_lines: i32, line_height: i32) -> i32 {
+    -((max_lines - lines) * line_height / 2)
+}
+
+/// Formats a row of [`Xosd::display_table`].
+///
+/// `value` is right-aligned so the row is `width` characters wide. Key and
+/// value are always separated by at least one space, so rows that don't fit
+/// are wider than `width`.
+fn table_row(key: &str, value: &str, width: usize) -> String {
+    let padding = width
+        .saturating_sub(key.chars().count() + value.chars().count())
+        .max(1);
+
+    format!("{}{:padding$}{}", key, "", value, padding = padding)
+}
+
+/// Word-wraps `text` into at most `max_lines` lines of at most `width`
+/// characters for [`Xosd::display_wrapped`].
+///
+/// Words longer than `width` are split. If the text needs more lines the last
+/// line is truncated and ends with `...`. A `width` of 0 is treated as 1.
+fn wrap_words(text: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+
+    for word in text.split_whitespace() {
+        let chars: Vec<char> = word.chars().collect();
+
+        for piece in chars.chunks(width) {
+            if current_len > 0 && current_len + 1 + piece.len() <= width {
+                current.push(' ');
+                current_len += 1;
+            } else if current_len > 0 {
+                lines.push(std::mem::take(&mut current));
+                current_len = 0;
+            }
+
+            current.extend(piece);
+            current_len += piece.len();
+        }
+    }
+
+    if current_len > 0 {
+        lines.push(current);
+    }
+
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+
+        if let Some(last) = lines.last_mut() {
+            *last = ellipsize(last, width);
+        }
+    }
+
+    lines
+}
+
+/// Cut `text` so that it ends with `...` and has at most `width` characters.
+///
+/// Widths below 3 keep only the dots that fit.
+fn ellipsize(text: &str, width: usize) -> String {
+    let mut truncated: String = text.chars().take(width.saturating_sub(3)).collect();
+    truncated.push_str(&"..."[..width.min(3)]);
+    truncated
+}
+
+/// Converts a color cached by [`Xosd`] to RGB8.
+///
+/// [`None`] is the xosd default black.
+fn cached_color(color: Option<&str>) -> Result<(u8, u8, u8)> {
+    let color = color.unwrap_or("black");
+
+    match parse_x11_color(color) {
+        Some(rgb) => Ok(rgb16_to_rgb8(rgb)),
+        None => Err(Error::InvalidColor(color.to_string())),
+    }
+}
+
+/// Converts `timeout` to whole seconds rounding up.
+fn timeout_seconds(timeout: Duration) -> Result<i32> {
+    let seconds = timeout.as_secs() + u64::from(timeout.subsec_nanos() > 0);
+
+    Ok(seconds.try_into()?)
+}
+
+/// Converts a fraction in `0.0..=1.0` to a rounded percentage.
+fn fraction_percentage(fraction: f32) -> Result<u16> {
+    if (0.0..=1.0).contains(&fraction) {
+        Ok((fraction * 100.0).round() as u16)
+    } else {
+        Err(Error::OutOfRangePercentage)
+    }
+}
+
+/// Returns the progressively longer prefixes of `text` ending with `text`.
+///
+/// Prefixes always end on a character boundary. An empty `text` yields a single
+/// empty prefix.
+fn prefixes(text: &str) -> impl Iterator<Item = &str> {
+    text.char_indices()
+        .map(|(index, _)| index)
+        .skip(1)
+        .chain(iter::once(text.len()))
+        .map(move |end| &text[..end])
+}
+
+/// Replace every emoji in `text` with its name in brackets.
+#[cfg(feature = "emoji")]
+fn demoji(text: &str) -> String {
+    /// The maximum number of characters an emoji sequence is made of
+    const MAX_EMOJI_CHARS: usize = 10;
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        // Emoji can be sequences of characters so try the longest match first.
+        let candidates: Vec<&str> = prefixes(rest).take(MAX_EMOJI_CHARS).collect();
+        let emoji = candidates.into_iter().rev().find_map(|candidate| {
+            emojis::get(candidate).map(|emoji| (candidate.len(), emoji.name()))
+        });
+
+        match emoji {
+            Some((len, name)) => {
+                result.push('[');
+                result.push_str(name);
+                result.push(']');
+                rest = &rest[len..];
+            }
+            None => {
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    result
+}
+
 /// Get the default color
 ///
 /// The XOSD library defines and uses a default color. This can be queries here.
@@ -153,11 +537,27 @@ pub fn default_font<'a>() -> Result<Cow<'a, str>> {
     wrap_static_string!(osd_default_font)
 }
 
+/// The version of the xosd library
+///
+/// xosd does not report its version at runtime. This is the version of the
+/// xosd installed when [`xosd_sys`] was built, as reported by
+/// `xosd-config --version`, or the version the bindings were written for if
+/// `xosd-config` was not found. The library loaded at runtime may differ.
+///
+/// # Example
+///
+/// ```
+/// println!("Using xosd {}", xosd_rs::XOSD_VERSION);
+/// ```
+pub const XOSD_VERSION: &str = xosd_sys::XOSD_VERSION;
+
 /// Various types that can be displayed with [`Xosd::display`]
 ///
 /// You should not construct any of these variants manually. Instead use one of
 /// the constructors below.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Command {
     /// Used to display a percentage
     Percentage(u16),
@@ -174,15 +574,38 @@ impl Command {
     ///
     /// # Errors
     ///
-    /// If precentage is greater than 100 or less than 1 return [`Error::OutOfRangePercentage`]
+    /// If precentage is greater than 100 return [`Error::OutOfRangePercentage`]
     pub fn percentage(percentage: u16) -> Result<Self> {
-        if percentage > 100 || percentage < 1 {
+        if percentage > 100 {
             Err(Error::OutOfRangePercentage)
         } else {
             Ok(Self::Percentage(percentage))
         }
     }
 
+    /// Construct the [`Command::Percentage`] variant from a fraction
+    ///
+    /// `fraction` is multiplied by 100 and rounded, so `0.475` is displayed as
+    /// 48%.
+    ///
+    /// # Errors
+    ///
+    /// If `fraction` is not in `0.0..=1.0` or is NaN
+    /// [`Error::OutOfRangePercentage`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Command;
+    /// assert_eq!(Command::percentage_f32(0.475)?, Command::Percentage(48));
+    /// assert!(Command::percentage_f32(f32::NAN).is_err());
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn percentage_f32(fraction: f32) -> Result<Self> {
+        Self::percentage(fraction_percentage(fraction)?)
+    }
+
     /// Construct the [`Command::String`] variant
     ///
     /// # Errors
@@ -195,25 +618,112 @@ impl Command {
         Ok(Self::String(string.to_string()))
     }
 
+    /// Construct the [`Command::String`] variant replacing emoji with text
+    ///
+    /// Legacy X11 fonts cannot render emoji. This replaces every emoji with its
+    /// name in brackets, e.g. `"🚀"` becomes `"[rocket]"`. This conversion is
+    /// lossy.
+    ///
+    /// This function is only available with the `emoji` feature.
+    ///
+    /// # Errors
+    ///
+    /// This function never fails
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Command;
+    /// assert_eq!(
+    ///     Command::string_demoji("Launching 🚀")?,
+    ///     Command::string("Launching [rocket]")?
+    /// );
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    #[cfg(feature = "emoji")]
+    pub fn string_demoji<S>(string: S) -> Result<Self>
+    where
+        S: AsRef<str>,
+    {
+        Ok(Self::String(demoji(string.as_ref())))
+    }
+
     /// Construct the [`Command::Slider`] variant
     ///
     /// # Errors
     ///
-    /// If precentage is greater than 100 or less than 1 return [`Error::OutOfRangePercentage`]
+    /// If precentage is greater than 100 return [`Error::OutOfRangePercentage`]
     pub fn slider(slider: u16) -> Result<Self> {
-        if slider > 100 || slider < 1 {
+        if slider > 100 {
             Err(Error::OutOfRangePercentage)
         } else {
             Ok(Self::Slider(slider))
         }
     }
+
+    /// Construct the [`Command::Slider`] variant from a fraction
+    ///
+    /// See [`Command::percentage_f32`].
+    ///
+    /// # Errors
+    ///
+    /// If `fraction` is not in `0.0..=1.0` or is NaN
+    /// [`Error::OutOfRangePercentage`] is returned.
+    pub fn slider_f32(fraction: f32) -> Result<Self> {
+        Self::slider(fraction_percentage(fraction)?)
+    }
+
+    /// The value of this command
+    ///
+    /// This is the value of the bar for [`Command::Percentage`] and
+    /// [`Command::Slider`] and the number of characters for
+    /// [`Command::String`]. For strings this differs from [`Xosd::display`],
+    /// which returns the number of bytes written, as soon as the string
+    /// contains non-ASCII characters.
+    ///
+    /// # Returns
+    ///
+    /// [`None`] if the string has more characters than fit into an `u16`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Command;
+    /// assert_eq!(Command::percentage(42)?.value(), Some(42));
+    /// assert_eq!(Command::string("Grüße")?.value(), Some(5));
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn value(&self) -> Option<u16> {
+        match self {
+            Self::Percentage(value) | Self::Slider(value) => Some(*value),
+            Self::String(string) => string.chars().count().try_into().ok(),
+        }
+    }
+}
+
+/// Formats the command for logs, e.g. `string: "hi"` or `percentage: 42%`
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Percentage(percentage) => write!(f, "percentage: {}%", percentage),
+            Self::String(string) => write!(f, "string: {:?}", string),
+            Self::Slider(slider) => write!(f, "slider: {}%", slider),
+        }
+    }
 }
 
 /// Represents the 3 different vertical alignments
 ///
 /// This enum is used in [`Xosd::set_vertical_align`]
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+///
+/// The default is [`VerticalAlign::Top`] which is what a new XOSD window uses.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum VerticalAlign {
+    #[default]
     Top,
     Center,
     Bottom,
@@ -233,6 +743,37 @@ impl fmt::Display for VerticalAlign {
     }
 }
 
+/// Parses the names written by [`Display`](fmt::Display) ignoring case
+///
+/// # Example
+///
+/// ```
+/// # use xosd_rs::VerticalAlign;
+/// assert_eq!("Bottom".parse::<VerticalAlign>()?, VerticalAlign::Bottom);
+///
+/// # Ok::<(), xosd_rs::Error>(())
+/// ```
+impl FromStr for VerticalAlign {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "top" => Ok(Self::Top),
+            "center" => Ok(Self::Center),
+            "bottom" => Ok(Self::Bottom),
+            _ => Err(Error::InvalidAlignment(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<&str> for VerticalAlign {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        s.parse()
+    }
+}
+
 #[doc(hidden)]
 impl Into<xosd_pos> for VerticalAlign {
     fn into(self) -> xosd_pos {
@@ -247,8 +788,14 @@ impl Into<xosd_pos> for VerticalAlign {
 /// Represents the 3 different horizontal alignments
 ///
 /// This enum is used in [`Xosd::set_horizontal_align`]
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+///
+/// The default is [`HorizontalAlign::Left`] which is what a new XOSD window
+/// uses.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum HorizontalAlign {
+    #[default]
     Left,
     Center,
     Right,
@@ -268,9 +815,40 @@ impl fmt::Display for HorizontalAlign {
     }
 }
 
+/// Parses the names written by [`Display`](fmt::Display) ignoring case
+///
+/// # Example
+///
+/// ```
+/// # use xosd_rs::HorizontalAlign;
+/// assert_eq!("Right".parse::<HorizontalAlign>()?, HorizontalAlign::Right);
+///
+/// # Ok::<(), xosd_rs::Error>(())
+/// ```
+impl FromStr for HorizontalAlign {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "left" => Ok(Self::Left),
+            "center" => Ok(Self::Center),
+            "right" => Ok(Self::Right),
+            _ => Err(Error::InvalidAlignment(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<&str> for HorizontalAlign {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        s.parse()
+    }
+}
+
 #[doc(hidden)]
 impl Into<xosd_align> for HorizontalAlign {
-    fn into(self) -> xosd_pos {
+    fn into(self) -> xosd_align {
         match self {
             Self::Left => xosd_align_XOSD_left,
             Self::Center => xosd_align_XOSD_center,
@@ -279,22 +857,141 @@ impl Into<xosd_align> for HorizontalAlign {
     }
 }
 
-#[derive(Debug, Clone, Hash)]
-pub struct Xosd(*mut xosd);
+/// Where [`Xosd::display_percentage_labeled`] displays the label
+///
+/// The default is [`LabelPosition::Below`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum LabelPosition {
+    /// The bar on line 0 and the label on line 1
+    #[default]
+    Below,
+    /// The label on line 0 and the bar on line 1
+    Above,
+}
+
+/// Represents the text encodings understood by [`Xosd::display_bytes`]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Encoding {
+    Utf8,
+    Latin1,
+    Ascii,
+}
 
-/// Calls the destructor for the XOSD object.
+impl Encoding {
+    /// Decode `bytes` into a string
+    ///
+    /// Invalid sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Encoding;
+    /// assert_eq!(Encoding::Latin1.decode(b"caf\xe9"), "café");
+    /// assert_eq!(Encoding::Ascii.decode(b"caf\xe9"), "caf\u{fffd}");
+    /// ```
+    pub fn decode(self, bytes: &[u8]) -> Cow<'_, str> {
+        match self {
+            Self::Utf8 => String::from_utf8_lossy(bytes),
+            Self::Latin1 => bytes.iter().map(|&b| char::from(b)).collect(),
+            Self::Ascii => bytes
+                .iter()
+                .map(|&b| match b.is_ascii() {
+                    true => char::from(b),
+                    false => char::REPLACEMENT_CHARACTER,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Utf8 => "utf-8",
+                Self::Latin1 => "latin-1",
+                Self::Ascii => "ascii",
+            }
+        )
+    }
+}
+
+type TextTransform = Arc<dyn Fn(&str) -> String + Send + Sync>;
+type ColorCallback = Arc<dyn Fn(Color) + Send + Sync>;
+
+/// A XOSD window
+///
+/// [`Xosd`] owns the xosd handle and destroys the window when dropped, so it
+/// can't be cloned:
+///
+/// ```compile_fail
+/// # use xosd_rs::Xosd;
+/// let osd = Xosd::new(1)?;
+/// let copy = osd.clone();
+///
+/// # Ok::<(), xosd_rs::Error>(())
+/// ```
+pub struct Xosd {
+    handle: *mut xosd,
+    text_transform: Option<TextTransform>,
+    on_color_change: Option<ColorCallback>,
+    fade_background: Color,
+    fade_color_space: ColorSpace,
+    state: Snapshot,
+    line_deadlines: Vec<Option<Instant>>,
+    burst_timeout: Option<i32>,
+    auto_grow: Option<i32>,
+}
+
+impl fmt::Debug for Xosd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Xosd")
+            .field("handle", &self.handle)
+            .field("text_transform", &self.text_transform.is_some())
+            .field("on_color_change", &self.on_color_change.is_some())
+            .field("fade_background", &self.fade_background)
+            .field("fade_color_space", &self.fade_color_space)
+            .field("state", &self.state)
+            .field("line_deadlines", &self.line_deadlines)
+            .field("burst_timeout", &self.burst_timeout)
+            .field("auto_grow", &self.auto_grow)
+            .finish()
+    }
+}
+
+/// Allows moving the XOSD object to another thread.
 ///
-/// # Panics
+/// The xosd library protects the state of a XOSD window with a mutex that is
+/// shared with its own event thread, so the handle may be used from any
+/// thread. [`Xosd`] is not [`Sync`]: every method takes `&mut self`, so it is
+/// used by one thread at a time.
+///
+/// The error message of the xosd library is a global variable. If two
+/// threads use different [`Xosd`] objects at the same time, an error may be
+/// reported with the message of the other thread.
+unsafe impl Send for Xosd {}
+
+impl Hash for Xosd {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.handle.hash(state)
+    }
+}
+
+/// Calls the destructor for the XOSD object.
 ///
-/// If `xsod_uninit` fails. Or if getting the error message fails after
-/// destroying the XOSD object fails.
+/// If `xosd_uninit` fails the error is logged as a warning with the `log`
+/// feature and printed to stderr without it. Use [`Xosd::close`] to handle the
+/// error instead.
 impl Drop for Xosd {
     fn drop(&mut self) {
-        if unsafe { xosd_uninit(self.0) } != 0 {
-            panic!(
-                "Could not destruct xosd instance: {}",
-                error_str().expect("the error message while panicing after `xosd_uninit` failed")
-            )
+        if self.handle.is_null() {
+            return;
+        }
+
+        if let Err(error) = self.uninit() {
+            warn!("Could not destruct xosd instance: {}", error);
         }
     }
 }
@@ -309,11 +1006,19 @@ impl Xosd {
     ///
     /// `lines` is the maximum number of lines that the window can display.
     ///
+    /// xosd always opens the X display named by the `DISPLAY` environment
+    /// variable and can't be told to use another display or screen. On a
+    /// multi-head desktop move the window to another monitor with
+    /// [`Xosd::set_horizontal_offset`] and [`Xosd::set_vertical_offset`]
+    /// instead.
+    ///
     /// # Errors
     ///
     /// * If `lines` is less than 1 [`Error::InvalidLineCount`] is returned.
-    /// * If `xosd_create` fails the xosd error message is wrapped in a
-    /// [`Error::XosdError`] and returned.
+    /// * If there is no X display to connect to, e.g. because `DISPLAY` is not
+    ///   set, [`Error::NoDisplay`] is returned.
+    /// * If `xosd_create` fails otherwise the xosd error message is wrapped in
+    ///   a [`Error::XosdError`] and returned.
     ///
     /// # Example
     ///
@@ -326,19 +1031,242 @@ impl Xosd {
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
     pub fn new<'a>(lines: i32) -> Result<Self> {
-        if lines == 0 {
+        if lines < 1 {
             return Err(Error::InvalidLineCount);
         }
 
-        let xosd = unsafe { xosd_create(lines.into()) };
+        let xosd = call_xosd!(xosd_create(lines.into()));
+        let lines = lines.try_into().unwrap_or_default();
 
         if !xosd.is_null() {
-            Ok(Self(xosd))
+            Ok(Self::from_handle(xosd, lines))
+        } else if display_unavailable() {
+            Err(Error::NoDisplay)
         } else {
-            Err(Error::XosdError(error_str()?.into_owned()))
+            Err(last_error())
+        }
+    }
+
+    fn from_handle(handle: *mut xosd, lines: usize) -> Self {
+        Self {
+            handle,
+            text_transform: None,
+            on_color_change: None,
+            fade_background: Color::rgb(0, 0, 0),
+            fade_color_space: ColorSpace::default(),
+            state: Snapshot::new(lines),
+            line_deadlines: vec![None; lines],
+            burst_timeout: None,
+            auto_grow: None,
         }
     }
 
+    /// Create a new [`Xosd`] object from a non-zero line count.
+    ///
+    /// This works like [`Xosd::new`] but the type of `lines` already rules out
+    /// an invalid line count.
+    ///
+    /// # Errors
+    ///
+    /// * If `lines` does not fit into an `i32` [`Error::TryFromIntError`] is
+    ///   returned.
+    /// * If `xosd_create` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::num::NonZeroU32;
+    /// # use xosd_rs::Xosd;
+    /// let lines = NonZeroU32::new(2).unwrap();
+    /// let mut osd = Xosd::new_nonzero(lines)?;
+    ///
+    /// assert_eq!(osd.max_lines()?, 2);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn new_nonzero(lines: NonZeroU32) -> Result<Self> {
+        Self::new(lines.get().try_into()?)
+    }
+
+    /// Create a [`Xosd`] object from a raw xosd handle.
+    ///
+    /// The [`Xosd`] takes ownership of the handle and destroys the window with
+    /// `xosd_uninit` when dropped. Settings changed before are not known, so
+    /// [`Xosd::snapshot`] and the getters based on it report the defaults.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be a valid handle returned by `xosd_create` that is not
+    /// destroyed or owned by anything else. It must not be used after the
+    /// [`Xosd`] was dropped. With the `mock` feature the handle must come from
+    /// [`Xosd::as_raw`] of a mocked [`Xosd`] that was then forgotten.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use xosd_rs::Xosd;
+    /// let handle = unsafe { xosd_sys::xosd_create(2) };
+    /// assert!(!handle.is_null());
+    ///
+    /// let mut osd = unsafe { Xosd::from_raw(handle) };
+    /// assert_eq!(osd.max_lines()?, 2);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub unsafe fn from_raw(handle: *mut xosd) -> Self {
+        let lines = call_xosd!(xosd_get_number_lines(handle));
+
+        Self::from_handle(handle, lines.try_into().unwrap_or_default())
+    }
+
+    /// Get the raw xosd handle.
+    ///
+    /// This is an escape hatch to call xosd functions from [`xosd_sys`] that
+    /// are not wrapped. The [`Xosd`] still owns the handle. Calling
+    /// `xosd_uninit` or anything else that frees the handle is undefined
+    /// behaviour, because the [`Xosd`] destroys it again when dropped. Changes
+    /// made through the handle are not reflected in [`Xosd::snapshot`].
+    ///
+    /// With the `mock` feature the handle does not point to a xosd window and
+    /// must not be passed to [`xosd_sys`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// unsafe { xosd_sys::xosd_set_timeout(osd.as_raw(), 3) };
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn as_raw(&self) -> *mut xosd {
+        self.handle
+    }
+
+    /// Create a XOSD window styled for notifications
+    ///
+    /// The window uses these settings, which can be changed afterwards with
+    /// the setters:
+    ///
+    /// * 2 lines
+    /// * font `-*-fixed-bold-*-*-*-18-*-*-*-*-*-*-*`
+    /// * white text with a black shadow offset by 2 pixels
+    /// * a timeout of 3 seconds
+    /// * aligned to the top center and offset by 40 pixels from the top
+    ///
+    /// # Errors
+    ///
+    /// See [`XosdBuilder::build`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::notification()?;
+    ///
+    /// osd.display_string(0, "New message")?;
+    /// osd.display_string(1, "Lunch is ready")?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn notification() -> Result<Self> {
+        XosdBuilder::new()
+            .lines(2)
+            .font("-*-fixed-bold-*-*-*-18-*-*-*-*-*-*-*")
+            .color("white")
+            .shadow_color("black")
+            .shadow_offset(2)
+            .timeout(3)
+            .vertical_align(VerticalAlign::Top)
+            .horizontal_align(HorizontalAlign::Center)
+            .vertical_offset(40)
+            .build()
+    }
+
+    /// Create a XOSD window styled for a volume bar
+    ///
+    /// The window is made to show a [`Command::Slider`] or
+    /// [`Command::Percentage`] and uses these settings, which can be changed
+    /// afterwards with the setters:
+    ///
+    /// * 1 line
+    /// * green bars with a black shadow offset by 1 pixel
+    /// * a timeout of 1 second
+    /// * aligned to the bottom center and offset by 60 pixels from the bottom
+    ///
+    /// # Errors
+    ///
+    /// See [`XosdBuilder::build`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::volume_bar()?;
+    ///
+    /// osd.display_slider(0, 70)?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn volume_bar() -> Result<Self> {
+        XosdBuilder::new()
+            .lines(1)
+            .color("LimeGreen")
+            .shadow_color("black")
+            .shadow_offset(1)
+            .timeout(1)
+            .vertical_align(VerticalAlign::Bottom)
+            .horizontal_align(HorizontalAlign::Center)
+            .vertical_offset(60)
+            .build()
+    }
+
+    /// Destroy the XOSD window
+    ///
+    /// Dropping a [`Xosd`] destroys the window as well, but can only print
+    /// errors. Use this to handle them.
+    ///
+    /// `close` consumes the [`Xosd`], so it can't be used afterwards, even if
+    /// `xosd_uninit` failed:
+    ///
+    /// ```compile_fail
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.close()?;
+    /// osd.show()?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_uninit` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let osd = Xosd::new(1)?;
+    ///
+    /// osd.close()?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn close(mut self) -> Result<()> {
+        self.uninit()
+    }
+
+    /// Destroy the window and leave a null handle so [`Drop`] skips it
+    fn uninit(&mut self) -> Result<()> {
+        let handle = std::mem::replace(&mut self.handle, std::ptr::null_mut());
+
+        wrap_unsafe!(xosd_uninit(handle))
+    }
+
     /// Change the length of the percentage bar or slider.
     ///
     /// This changes the percentage of the display used by a slider or percentage
@@ -376,9 +1304,12 @@ impl Xosd {
         }
 
         wrap_unsafe!(xosd_set_bar_length(
-            self.0,
+            self.handle,
             percentage.map(|v| v.into()).unwrap_or(-1)
-        ))
+        ))?;
+        self.state.bar_length = percentage;
+
+        Ok(())
     }
 
     /// Display data to an XOSD window.
@@ -391,6 +1322,8 @@ impl Xosd {
     /// the data is not displayed anymore. A window that is displaying data can
     /// be hidden by calling [`Xosd::hide`].
     ///
+    /// A negative `line` counts from the last line, so `-1` is the last line.
+    ///
     /// # Returns
     ///
     /// * If `command` is [`Command::String`] the number of characters written is
@@ -400,8 +1333,14 @@ impl Xosd {
     ///
     /// # Errors
     ///
+    /// * If `line` is not in `-max_lines()..max_lines()` [`Error::InvalidLine`]
+    ///   is returned.
+    /// * If a [`Command::String`] contains a NUL byte [`Error::InvalidString`]
+    ///   is returned.
     /// * If `xosd_display` fails the xosd error message is wrapped in a
     /// [`Error::XosdError`] and returned.
+    /// * If the value returned by `xosd_display` does not fit into an `u16`
+    ///   [`Error::TryFromIntError`] is returned.
     ///
     /// # Example
     ///
@@ -414,419 +1353,659 @@ impl Xosd {
     ///
     /// assert_eq!(osd.display(1, Command::percentage(13)?)?, 13);
     ///
+    /// // Update the last line
+    /// osd.display(-1, Command::percentage(14)?)?;
+    ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
     pub fn display(&mut self, line: i32, command: Command) -> Result<u16> {
-        let res = match command {
-            Command::Percentage(percentage) => unsafe {
-                xosd_display(
-                    self.0,
-                    line.into(),
-                    xosd_command_XOSD_percentage,
-                    percentage as c_uint,
-                )
-            },
-            Command::String(string) => unsafe {
-                xosd_display(
-                    self.0,
-                    line.into(),
-                    xosd_command_XOSD_string,
-                    CString::new(string)?,
-                )
-            },
-            Command::Slider(slider) => unsafe {
-                xosd_display(
-                    self.0,
-                    line.into(),
-                    xosd_command_XOSD_slider,
-                    slider as c_uint,
-                )
-            },
-        };
+        let command = self.transform_command(command);
 
-        if res < 0 {
-            Err(Error::XosdError(error_str()?.into_owned()))
-        } else {
-            Ok(res
-                .try_into()
-                .expect("res is positive and should fit into u16"))
-        }
+        self.display_transformed(line, command)
     }
 
-    /// Returns wether the XOSD window is shown.
+    /// Display data on a line unless it already shows it.
     ///
-    /// Determines wether a XOSD window is currently beeing shown.
+    /// `command` is compared with the command last displayed on `line`, after
+    /// the text transform was applied. If they are equal and the window is
+    /// shown `xosd_display` is not called, which avoids flicker when the same
+    /// value is displayed repeatedly. If the window was hidden, for example
+    /// because the timeout elapsed, the data is displayed again.
     ///
-    /// Use [`Xosd::show`] and [`Xosd::hide`] to alter the visibility of the XOSD
-    /// window.
+    /// # Returns
+    ///
+    /// [`None`] if nothing was displayed, otherwise the value returned by
+    /// [`Xosd::display`].
     ///
     /// # Errors
     ///
-    /// * If `xosd_is_onscreen` fails the xosd error message is wrapped in a
-    /// [`Error::XosdError`] and returned.
+    /// See [`Xosd::display`] and [`Xosd::onscreen`].
     ///
     /// # Example
     ///
     /// ```
-    /// # use xosd_rs::Xosd;
+    /// # use xosd_rs::{Xosd, Command};
     /// let mut osd = Xosd::new(1)?;
     ///
-    /// assert_eq!(osd.onscreen()?, false);
-    ///
-    /// osd.show()?;
-    ///
-    /// assert_eq!(osd.onscreen()?, true);
+    /// assert_eq!(osd.display_if_changed(0, Command::slider(40)?)?, Some(40));
+    /// assert_eq!(osd.display_if_changed(0, Command::slider(40)?)?, None);
+    /// assert_eq!(osd.display_if_changed(0, Command::slider(45)?)?, Some(45));
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn onscreen(&mut self) -> Result<bool> {
-        match unsafe { xosd_is_onscreen(self.0) } {
-            1 => Ok(true),
-            0 => Ok(false),
-            _ => Err(Error::XosdError(error_str()?.into_owned())),
+    pub fn display_if_changed(&mut self, line: i32, command: Command) -> Result<Option<u16>> {
+        let command = self.transform_command(command);
+
+        let cached = self
+            .absolute_line(line)
+            .ok()
+            .and_then(|line| usize::try_from(line).ok())
+            .and_then(|index| self.state.lines.get(index))
+            .and_then(Option::as_ref);
+        if cached == Some(&command) && self.onscreen()? {
+            return Ok(None);
         }
+
+        self.display_transformed(line, command).map(Some)
     }
 
-    /// Wait until the XOSD window is not displaying anything.
+    /// Apply the text transform to a [`Command::String`]
+    fn transform_command(&self, command: Command) -> Command {
+        match command {
+            Command::String(string) => Command::String(self.transform_text(string)),
+            command => command,
+        }
+    }
+
+    /// Like [`Xosd::display`] but `command` is already transformed
+    fn display_transformed(&mut self, line: i32, command: Command) -> Result<u16> {
+        let line = self.absolute_line(line)?;
+
+        if line >= 0 {
+            self.grow(line.saturating_add(1))?;
+        }
+        self.check_cached_line(line)?;
+
+        self.display_raw(line, command)
+    }
+
+    fn display_raw(&mut self, line: i32, command: Command) -> Result<u16> {
+        debug!("display on line {}: {}", line, command);
+
+        let res = match &command {
+            Command::Percentage(percentage) => call_xosd!(xosd_display(
+                self.handle,
+                line.into(),
+                xosd_command_XOSD_percentage,
+                *percentage as c_uint,
+            )),
+            Command::String(string) => {
+                let string = field_cstring(string.as_str(), "string")?;
+
+                call_xosd!(xosd_display(
+                    self.handle,
+                    line.into(),
+                    xosd_command_XOSD_string,
+                    string.as_ptr(),
+                ))
+            }
+            Command::Slider(slider) => call_xosd!(xosd_display(
+                self.handle,
+                line.into(),
+                xosd_command_XOSD_slider,
+                *slider as c_uint,
+            )),
+        };
+
+        let res = display_result(res)?;
+        if let Some(cached) = self.state.lines.get_mut(line as usize) {
+            *cached = Some(command);
+        }
+        if let Some(deadline) = self.line_deadlines.get_mut(line as usize) {
+            *deadline = None;
+        }
+
+        Ok(res)
+    }
+
+    /// Display text on a line
     ///
-    /// Block until the timeout limit is reached and nothing is displayed on the XOSD window.
+    /// This is a shortcut for `display(line, Command::string(text)?)`.
+    ///
+    /// # Returns
+    ///
+    /// The number of characters written.
     ///
     /// # Errors
     ///
-    /// * If `xosd_wait_until_no_display` fails the xosd error message is wrapped in a
-    /// [`Error::XosdError`] and returned.
+    /// See [`Xosd::display`].
     ///
     /// # Example
     ///
     /// ```
-    /// # use xosd_rs::{Xosd, Command};
+    /// # use xosd_rs::Xosd;
     /// let mut osd = Xosd::new(1)?;
-    /// osd.set_timeout(1)?;
     ///
-    /// osd.display(0, Command::string("Example XOSD output")?)?;
-    ///
-    /// if osd.onscreen()? {
-    ///     osd.wait_until_no_display()?;
-    /// }
+    /// assert_eq!(osd.display_string(0, "Hello")?, 5);
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn wait_until_no_display(&mut self) -> Result<()> {
-        wrap_unsafe!(xosd_wait_until_no_display(self.0))
+    pub fn display_string<S>(&mut self, line: i32, text: S) -> Result<u16>
+    where
+        S: ToString,
+    {
+        self.display(line, Command::string(text)?)
     }
 
-    /// Hide the XOSD window
+    /// Display a percentage bar on a line
     ///
-    /// This unmaps the XOSD window. Use [`Xosd::show`] to remap it.
+    /// This is a shortcut for `display(line, Command::percentage(percentage)?)`.
     ///
-    /// If [`Xosd::display`] is used when the window is not visible, the window becomes visible again.
+    /// # Returns
+    ///
+    /// The value of the bar.
     ///
     /// # Errors
     ///
-    /// * If `xosd_hide` fails the xosd error message is wrapped in a
-    /// [`Error::XosdError`] and returned.
+    /// * If `percentage` is greater than 100 [`Error::OutOfRangePercentage`] is
+    ///   returned.
+    /// * See [`Xosd::display`] for the other errors.
     ///
     /// # Example
     ///
-    /// ```ignore
-    /// # use xosd_rs::{Xosd, Command};
+    /// ```
+    /// # use xosd_rs::Xosd;
     /// let mut osd = Xosd::new(1)?;
     ///
-    /// assert!(!osd.onscreen()?, "XOSD window initializes as hidden");
-    ///
-    /// osd.display(0, Command::string("Example XOSD output")?)?;
-    ///
-    /// assert!(osd.onscreen()?, "using Xosd::display shows the display");
-    ///
-    /// osd.hide()?;
-    ///
-    /// assert!(!osd.onscreen()?, "after using Xosd::hide the windows is not visible anymore");
+    /// assert_eq!(osd.display_percentage(0, 42)?, 42);
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    // BUG: example fails
-    pub fn hide(&mut self) -> Result<()> {
-        wrap_unsafe!(xosd_hide(self.0))
+    pub fn display_percentage(&mut self, line: i32, percentage: u16) -> Result<u16> {
+        self.display(line, Command::percentage(percentage)?)
     }
 
-    /// Show the XOSD window
+    /// Display a slider on a line
     ///
-    /// Redisplay the data that has been previously displayed by [`Xosd::display`].
+    /// This is a shortcut for `display(line, Command::slider(slider)?)`.
+    ///
+    /// # Returns
+    ///
+    /// The value of the slider.
     ///
     /// # Errors
     ///
-    /// * If `xosd_show` fails the xosd error message is wrapped in a
-    /// [`Error::XosdError`] and returned.
+    /// * If `slider` is greater than 100 [`Error::OutOfRangePercentage`] is
+    ///   returned.
+    /// * See [`Xosd::display`] for the other errors.
     ///
     /// # Example
     ///
-    /// ```ignore
-    /// # use xosd_rs::{Xosd, Command};
+    /// ```
+    /// # use xosd_rs::Xosd;
     /// let mut osd = Xosd::new(1)?;
     ///
-    /// assert!(!osd.onscreen()?, "XOSD window initializes as hidden");
-    ///
-    /// osd.display(0, Command::string("Example XOSD output")?)?;
-    ///
-    /// assert!(osd.onscreen()?, "using Xosd::display shows the display");
-    ///
-    /// osd.hide()?;
-    ///
-    /// assert!(!osd.onscreen()?, "after using Xosd::hide the windows is not visible anymore");
-    ///
-    /// osd.show()?;
-    ///
-    /// assert!(!osd.onscreen()?, "after using Xosd::show the windows is visible again");
+    /// assert_eq!(osd.display_slider(0, 42)?, 42);
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    // BUG: example fails
-    pub fn show(&mut self) -> Result<()> {
-        wrap_unsafe!(xosd_show(self.0))
+    pub fn display_slider(&mut self, line: i32, slider: u16) -> Result<u16> {
+        self.display(line, Command::slider(slider)?)
     }
 
-    /// Change the vertical alignment of the XOSD window
+    /// Display a percentage bar or slider in another color than the text
+    ///
+    /// xosd has no bar color, bars are drawn in the text color. As a
+    /// workaround this sets the text color to `color` right before the bar is
+    /// displayed and restores the previous color afterwards. The color belongs
+    /// to the whole window, so text displayed at the same time may be drawn in
+    /// `color` too.
+    ///
+    /// The color does not survive a redraw. Whenever xosd draws the window
+    /// again, e.g. when another line is displayed or the window is shown after
+    /// being hidden, the bar is drawn in the restored text color. The callback
+    /// set with [`Xosd::set_on_color_change`] is not called.
+    ///
+    /// # Returns
+    ///
+    /// The value of the bar.
     ///
     /// # Errors
     ///
-    /// * If `xosd_set_pos` fails the xosd error message is wrapped in a
-    /// [`Error::XosdError`] and returned.
+    /// * If `command` is a [`Command::String`] [`Error::NotABar`] is returned.
+    /// * See [`Xosd::set_color`] and [`Xosd::display`] for the other errors.
+    ///   The previous color is restored even if displaying the bar failed.
     ///
     /// # Example
     ///
     /// ```
-    /// # use xosd_rs::{Xosd, VerticalAlign};
-    /// let mut osd = Xosd::new(1)?;
+    /// # use xosd_rs::{Command, Xosd};
+    /// let mut osd = Xosd::new(2)?;
+    /// osd.set_color("white")?;
     ///
-    /// osd.set_vertical_align(VerticalAlign::Top)?;
+    /// osd.display_string(0, "Battery")?;
+    /// osd.display_bar_colored(1, Command::percentage(15)?, "red")?;
+    ///
+    /// assert_eq!(osd.color()?, (255, 255, 255));
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn set_vertical_align(&mut self, align: VerticalAlign) -> Result<()> {
-        wrap_unsafe!(xosd_set_pos(self.0, align.into()))
+    pub fn display_bar_colored(&mut self, line: i32, command: Command, color: &str) -> Result<u16> {
+        if let Command::String(_) = command {
+            return Err(Error::NotABar);
+        }
+
+        let previous = self.state.color.clone();
+
+        self.set_raw_color(color)?;
+        let res = self.display(line, command);
+        let restored = self.restore_color(previous);
+
+        let value = res?;
+        restored?;
+
+        Ok(value)
     }
 
-    /// Change the horizontal alignment of the XOSD window
+    /// Clear a line
+    ///
+    /// Displays an empty string on `line`. Unlike [`Xosd::display`] the text
+    /// transform is not applied and the window does not grow. Like
+    /// [`Xosd::display`] a negative `line` counts from the last line.
     ///
     /// # Errors
     ///
-    /// * If `xosd_set_align` fails the xosd error message is wrapped in a
-    /// [`Error::XosdError`] and returned.
+    /// * If `line` is less than `-max_lines()` [`Error::InvalidLine`] is
+    ///   returned.
+    /// * If `line` is not less than `max_lines()` xosd rejects it and its error
+    ///   message is wrapped in a [`Error::XosdError`] and returned.
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
     ///
     /// # Example
     ///
     /// ```
-    /// # use xosd_rs::{Xosd, HorizontalAlign};
-    /// let mut osd = Xosd::new(1)?;
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(2)?;
     ///
-    /// osd.set_horizontal_align(HorizontalAlign::Right)?;
+    /// osd.display_string(0, "Connecting...")?;
+    /// osd.display_string(1, "Stale status")?;
+    /// osd.clear_line(1)?;
+    ///
+    /// // The display shows:
+    /// // Connecting...
+    ///
+    /// assert!(osd.clear_line(2).is_err());
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn set_horizontal_align(&mut self, align: HorizontalAlign) -> Result<()> {
-        wrap_unsafe!(xosd_set_align(self.0, align.into()))
+    pub fn clear_line(&mut self, line: i32) -> Result<()> {
+        let line = self.absolute_line(line)?;
+        self.display_raw(line, Command::String(String::new()))?;
+
+        Ok(())
     }
 
-    /// Change the shadow offset of the XOSD window
+    /// Clear every line
     ///
-    /// XOSD draws a shadow beneath the main XOSD window to increase readability.
+    /// Calls [`Xosd::clear_line`] for every line up to [`Xosd::max_lines`].
     ///
-    /// Change the size of the XOSD window by altering how many pixels the shadow
-    /// is offset to the bottom-right. One to four pixels result in a good
-    /// effect.
+    /// # Errors
+    ///
+    /// * If `xosd_get_number_lines` or `xosd_display` fails the xosd error
+    ///   message is wrapped in a [`Error::XosdError`] and returned.
+    pub fn clear_all(&mut self) -> Result<()> {
+        for line in 0..self.max_lines()? {
+            self.clear_line(line)?;
+        }
+
+        Ok(())
+    }
+
+    /// Display data and hide the window when the returned guard is dropped
+    ///
+    /// This is useful to show something while computing and hide it once the
+    /// scope ends, even on early returns. See [`DisplayGuard`].
     ///
     /// # Errors
     ///
-    /// * If `xosd_set_shadow_offset` fails the xosd error message is wrapped in
-    /// a [`Error::XosdError`] and returned.
+    /// See [`Xosd::display`].
     ///
     /// # Example
     ///
     /// ```
-    /// # use xosd_rs::Xosd;
+    /// # use xosd_rs::{Command, Xosd};
     /// let mut osd = Xosd::new(1)?;
     ///
-    /// osd.set_shadow_offset(2)?;
+    /// {
+    ///     let _guard = osd.display_scoped(0, Command::string("Saving...")?)?;
+    ///     // save something
+    /// }
+    ///
+    /// assert!(!osd.onscreen()?);
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn set_shadow_offset(&mut self, offset: i32) -> Result<()> {
-        wrap_unsafe!(xosd_set_shadow_offset(self.0, offset))
+    pub fn display_scoped(&mut self, line: i32, command: Command) -> Result<DisplayGuard<'_>> {
+        self.display(line, command)?;
+
+        Ok(DisplayGuard::new(self))
     }
 
-    /// Change the outline offset of the text
+    /// Display data on a single line and mark it to be cleared by
+    /// [`Xosd::expire_lines`], which the caller must call.
     ///
-    /// XOSD draws a outline around the text on the XOSD window.
+    /// Nothing is cleared without a call to [`Xosd::expire_lines`]: no thread
+    /// or timer is started, so the line stays until it is called after
+    /// `duration` has passed. Call it from your own timer or event loop,
+    /// possibly sleeping until [`Xosd::line_deadline`], or use
+    /// [`LineTimer::display_line_for`] to clear the line in a background
+    /// thread.
     ///
-    /// Change the thickness of the outline in pixels.
+    /// xosd only has a timeout for the whole window, so this simulates a
+    /// timeout for a single line. Other lines stay untouched. Displaying
+    /// something else on `line` before the line expired cancels the clear.
+    /// The window timeout set with [`Xosd::set_timeout`] still applies to
+    /// every line.
+    ///
+    /// A negative `line` counts from the last line.
+    ///
+    /// # Returns
+    ///
+    /// The same value [`Xosd::display`] returns.
     ///
     /// # Errors
     ///
-    /// * If `xosd_set_outline_offset` fails the xosd error message is wrapped in
-    /// a [`Error::XosdError`] and returned.
+    /// See [`Xosd::display`].
     ///
     /// # Example
     ///
     /// ```
-    /// # use xosd_rs::Xosd;
-    /// let mut osd = Xosd::new(1)?;
+    /// # use std::time::{Duration, Instant};
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(2)?;
     ///
-    /// osd.set_outline_offset(2)?;
+    /// osd.display(0, Command::string("Battery: 80%")?)?;
+    /// osd.display_line_for(1, Command::string("Charger connected")?, Duration::from_millis(20))?;
+    ///
+    /// while let Some(deadline) = osd.line_deadline() {
+    ///     std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+    ///     osd.expire_lines()?;
+    /// }
+    ///
+    /// // The display shows:
+    /// // Battery: 80%
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn set_outline_offset(&mut self, offset: i32) -> Result<()> {
-        wrap_unsafe!(xosd_set_outline_offset(self.0, offset))
+    pub fn display_line_for(
+        &mut self,
+        line: i32,
+        command: Command,
+        duration: Duration,
+    ) -> Result<u16> {
+        let line = self.absolute_line(line)?;
+        let res = self.display(line, command)?;
+
+        if let Some(deadline) = self.line_deadlines.get_mut(line as usize) {
+            *deadline = Some(Instant::now() + duration);
+        }
+
+        Ok(res)
     }
 
-    /// Set the shadow color of the XOSD window
+    /// Returns when the next line displayed with [`Xosd::display_line_for`]
+    /// should be cleared.
     ///
-    /// Change the color to one defined by X11 in
-    /// [`rgb.txt`](https://gitlab.freedesktop.org/xorg/app/rgb/raw/master/rgb.txt).
+    /// Returns [`None`] if no line is waiting to be cleared.
+    pub fn line_deadline(&self) -> Option<Instant> {
+        self.line_deadlines.iter().flatten().min().copied()
+    }
+
+    /// Clear every line displayed with [`Xosd::display_line_for`] whose
+    /// duration has passed.
     ///
     /// # Errors
     ///
-    /// * If `xosd_set_shadow_colour` fails the xosd error message is wrapped in
-    /// a [`Error::XosdError`] and returned.
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    pub fn expire_lines(&mut self) -> Result<()> {
+        self.expire_lines_at(Instant::now())
+    }
+
+    fn expire_lines_at(&mut self, now: Instant) -> Result<()> {
+        for line in 0..self.line_deadlines.len() {
+            if self.line_deadlines[line].is_some_and(|deadline| now >= deadline) {
+                self.display(line.try_into()?, Command::String(String::new()))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Set a transform that is applied to every displayed string.
+    ///
+    /// The transform is called by [`Xosd::display`] and every helper built on
+    /// top of it with the text of each [`Command::String`] before it is passed
+    /// to the XOSD library. This is useful for text policies that apply to the
+    /// whole application, like stripping characters the font cannot render.
+    ///
+    /// Setting a new transform replaces the previous one.
     ///
     /// # Example
     ///
     /// ```
-    /// # use xosd_rs::Xosd;
+    /// # use xosd_rs::{Xosd, Command};
     /// let mut osd = Xosd::new(1)?;
     ///
-    /// osd.set_shadow_color("White")?;
+    /// osd.set_text_transform(|text| text.to_uppercase());
+    ///
+    /// // The display shows:
+    /// // HELLO, WORLD!
+    /// osd.display(0, Command::string("Hello, World!")?)?;
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn set_shadow_color<S>(&mut self, color: S) -> Result<()>
+    pub fn set_text_transform<F>(&mut self, transform: F)
     where
-        S: Into<Vec<u8>>,
+        F: Fn(&str) -> String + Send + Sync + 'static,
     {
-        wrap_unsafe!(xosd_set_shadow_colour(
-            self.0,
-            CString::new(color)?.as_ptr()
-        ))
+        self.text_transform = Some(Arc::new(transform));
     }
 
-    /// Set the outline color of the text
+    /// Remove the transform set with [`Xosd::set_text_transform`].
+    pub fn clear_text_transform(&mut self) {
+        self.text_transform = None;
+    }
+
+    fn transform_text(&self, text: String) -> String {
+        match &self.text_transform {
+            Some(transform) => transform(&text),
+            None => text,
+        }
+    }
+
+    /// Display a percentage bar together with its value as text.
     ///
-    /// Change the color to one defined by X11 in
-    /// [`rgb.txt`](https://gitlab.freedesktop.org/xorg/app/rgb/raw/master/rgb.txt).
+    /// The value is formatted as `"{value}%"`. With [`LabelPosition::Below`]
+    /// the bar is displayed on line 0 and the label on line 1, with
+    /// [`LabelPosition::Above`] the other way around. This is the common
+    /// layout for volume or brightness indicators. The XOSD window needs at
+    /// least 2 lines.
     ///
     /// # Errors
     ///
-    /// * If `xosd_set_outline_colour` fails the xosd error message is wrapped in
-    /// a [`Error::XosdError`] and returned.
+    /// * If `value` is greater than 100 [`Error::OutOfRangePercentage`] is
+    ///   returned.
+    /// * If the window has less than 2 lines [`Error::InvalidLine`] is returned.
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
     ///
     /// # Example
     ///
     /// ```
-    /// # use xosd_rs::Xosd;
-    /// let mut osd = Xosd::new(1)?;
+    /// # use xosd_rs::{LabelPosition, Xosd};
+    /// let mut osd = Xosd::new(2)?;
     ///
-    /// osd.set_outline_color("Grey")?;
+    /// osd.display_percentage_labeled(47, LabelPosition::Below)?;
+    ///
+    /// // The display shows:
+    /// // ||||||||||||
+    /// // 47%
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn set_outline_color<S>(&mut self, color: S) -> Result<()>
-    where
-        S: Into<Vec<u8>>,
-    {
-        wrap_unsafe!(xosd_set_shadow_colour(
-            self.0,
-            CString::new(color)?.as_ptr()
-        ))
+    pub fn display_percentage_labeled(&mut self, value: u16, label: LabelPosition) -> Result<()> {
+        if value > 100 {
+            return Err(Error::OutOfRangePercentage);
+        }
+
+        let max = self.max_lines()?;
+        if max < 2 {
+            return Err(Error::InvalidLine { line: 1, max });
+        }
+
+        let (bar_line, label_line) = match label {
+            LabelPosition::Below => (0, 1),
+            LabelPosition::Above => (1, 0),
+        };
+
+        self.display(bar_line, Command::Percentage(value))?;
+        self.display(label_line, Command::String(format!("{}%", value)))?;
+
+        Ok(())
     }
 
-    /// Change the horizontal offset of the XOSD window
+    /// Display the same data on every line of the XOSD window.
     ///
-    /// Changes the number of pixels the XOSD window is offset from left of the
-    /// screen.
+    /// `command` is displayed on every line from 0 up to [`Xosd::max_lines`].
+    ///
+    /// # Returns
+    ///
+    /// The values returned by [`Xosd::display`] for each line in order.
     ///
     /// # Errors
     ///
-    /// * If `xosd_set_horizontal_offset` fails the xosd error message is wrapped in
-    /// a [`Error::XosdError`] and returned.
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
     ///
     /// # Example
     ///
     /// ```
-    /// # use xosd_rs::Xosd;
-    /// let mut osd = Xosd::new(1)?;
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(3)?;
     ///
-    /// osd.set_horizontal_offset(20)?;
+    /// assert_eq!(osd.broadcast(Command::percentage(50)?)?, vec![50, 50, 50]);
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn set_horizontal_offset(&mut self, offset: i32) -> Result<()> {
-        wrap_unsafe!(xosd_set_horizontal_offset(self.0, offset))
+    pub fn broadcast(&mut self, command: Command) -> Result<Vec<u16>> {
+        (0..self.max_lines()?)
+            .map(|line| self.display(line, command.clone()))
+            .collect()
     }
 
-    /// Change the vertical offset of the XOSD window
+    /// Display data on consecutive lines starting at line 0.
     ///
-    /// Changes the number of pixels the XOSD window is offset from the top or
-    /// bottom of the screen. Set this to 48 to avoid desktop-panels like in
-    /// GNOME or KDE.
+    /// Lines after the last item keep what they show.
     ///
     /// # Errors
     ///
-    /// * If `xosd_set_vertical_offset` fails the xosd error message is wrapped in
-    /// a [`Error::XosdError`] and returned.
+    /// * If `commands` yields more items than the XOSD window has lines
+    ///   [`Error::TooManyLines`] is returned before anything is displayed.
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
     ///
     /// # Example
     ///
     /// ```
-    /// # use xosd_rs::Xosd;
-    /// let mut osd = Xosd::new(1)?;
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(3)?;
     ///
-    /// osd.set_vertical_offset(48)?;
+    /// let tracks = vec!["1. Intro".to_string(), "2. Outro".to_string()];
+    /// osd.display_lines(tracks.into_iter().map(Command::String))?;
+    ///
+    /// // The display shows:
+    /// // 1. Intro
+    /// // 2. Outro
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn set_vertical_offset(&mut self, offset: i32) -> Result<()> {
-        wrap_unsafe!(xosd_set_vertical_offset(self.0, offset))
+    pub fn display_lines<I>(&mut self, commands: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Command>,
+    {
+        let commands: Vec<_> = commands.into_iter().collect();
+        self.check_line_count(commands.len())?;
+
+        for (line, command) in (0..).zip(commands) {
+            self.display(line, command)?;
+        }
+
+        Ok(())
     }
 
-    /// Change the time until the XOSD window is hidden.
+    /// Display text word-wrapped on consecutive lines.
     ///
-    /// Changes the number of seconds to wait after displaying data to hide the
-    /// XOSD window.
+    /// `text` is split at whitespace into lines of at most `width_chars`
+    /// characters, which are displayed starting at `start_line`. Words longer
+    /// than `width_chars` are split. If the text does not fit on the lines
+    /// from `start_line` to the last line, the last line ends with `...`.
+    /// Other lines keep what they show. Like [`Xosd::display`] a negative
+    /// `start_line` counts from the last line.
     ///
     /// # Errors
     ///
-    /// * If `xosd_set_timeout` fails the xosd error message is wrapped in
-    /// a [`Error::XosdError`] and returned.
+    /// * If `start_line` is not a line of the XOSD window
+    ///   [`Error::InvalidLine`] is returned.
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
     ///
     /// # Example
     ///
     /// ```
-    /// # use xosd_rs::{Xosd, Command};
-    /// let mut osd = Xosd::new(1)?;
-    ///
-    /// osd.set_timeout(3)?;
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(2)?;
     ///
-    /// osd.display(0, Command::string("Test")?)?;
+    /// osd.display_wrapped(0, "The quick brown fox jumps over the lazy dog", 16)?;
     ///
-    /// if osd.onscreen()? {
-    ///     osd.wait_until_no_display()?;
-    /// }
+    /// // The display shows:
+    /// // The quick brown
+    /// // fox jumps ove...
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn set_timeout(&mut self, timeout: u16) -> Result<()> {
-        wrap_unsafe!(xosd_set_timeout(self.0, timeout.into()))
+    pub fn display_wrapped(
+        &mut self,
+        start_line: i32,
+        text: &str,
+        width_chars: usize,
+    ) -> Result<()> {
+        let max = self.max_lines()?;
+        let start_line = self.absolute_line(start_line)?;
+        self.check_line(start_line)?;
+
+        let lines = wrap_words(text, width_chars, (max - start_line) as usize);
+        for (line, text) in (start_line..).zip(lines) {
+            self.display(line, Command::String(text))?;
+        }
+
+        Ok(())
     }
 
-    /// Change the text color
+    /// Display text cut to a maximum number of characters.
     ///
-    /// Change the color to one defined by X11 in
-    /// [`rgb.txt`](https://gitlab.freedesktop.org/xorg/app/rgb/raw/master/rgb.txt).
+    /// If `text` has more than `max_chars` characters it is cut and ends with
+    /// `...`, so at most `max_chars` characters are displayed. Characters are
+    /// counted as Unicode scalar values, not bytes.
+    ///
+    /// # Returns
+    ///
+    /// The number of characters written.
     ///
     /// # Errors
     ///
-    /// * If `xosd_set_colour` fails the xosd error message is wrapped in a
-    /// [`Error::XosdError`] and returned.
+    /// See [`Xosd::display`].
     ///
     /// # Example
     ///
@@ -834,163 +2013,4689 @@ impl Xosd {
     /// # use xosd_rs::Xosd;
     /// let mut osd = Xosd::new(1)?;
     ///
-    /// osd.set_color("LimeGreen")?;
+    /// osd.display_truncated(0, "Never Gonna Give You Up", 16)?;
+    ///
+    /// // The display shows:
+    /// // Never Gonna G...
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn set_color<S>(&mut self, color: S) -> Result<()>
-    where
-        S: Into<Vec<u8>>,
-    {
-        wrap_unsafe!(xosd_set_colour(self.0, CString::new(color)?.as_ptr()))
+    pub fn display_truncated(&mut self, line: i32, text: &str, max_chars: usize) -> Result<u16> {
+        let text = if text.chars().count() > max_chars {
+            ellipsize(text, max_chars)
+        } else {
+            text.to_string()
+        };
+
+        self.display(line, Command::String(text))
     }
 
-    /// Change the text font
+    /// Display lines as a block centered on the screen.
     ///
-    /// Changes the font used to render text on the XOSD window. A X11 font
-    /// description name must be passed.
+    /// This centers the XOSD window vertically and changes the vertical offset
+    /// so the displayed lines, and not the whole window, are centered. Lines
+    /// below the block are cleared.
     ///
+    /// XOSD does not expose the height of a line so `line_height` must be
+    /// given in pixels.
     ///
     /// # Errors
     ///
-    /// * If `xosd_set_font` fails the xosd error message is wrapped in a
-    /// [`Error::XosdError`] and returned.
+    /// * If there are more `lines` than the XOSD window can display
+    ///   [`Error::TooManyLines`] is returned.
+    /// * If any of the underlying xosd functions fails the xosd error message
+    ///   is wrapped in a [`Error::XosdError`] and returned.
     ///
     /// # Example
     ///
     /// ```
     /// # use xosd_rs::Xosd;
-    /// let mut osd = Xosd::new(1)?;
+    /// let mut osd = Xosd::new(5)?;
     ///
-    /// osd.set_font("fixed")?;
+    /// osd.display_block_centered(&["Now playing:", "Never Gonna Give You Up"], 20)?;
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn set_font<S>(&mut self, font: S) -> Result<()>
-    where
-        S: Into<Vec<u8>>,
-    {
-        wrap_unsafe!(xosd_set_font(self.0, CString::new(font)?.as_ptr()))
+    pub fn display_block_centered(&mut self, lines: &[&str], line_height: i32) -> Result<()> {
+        let count = self.check_line_count(lines.len())?;
+        let max = self.max_lines()?;
+
+        self.set_vertical_align(VerticalAlign::Center)?;
+        self.set_vertical_offset(block_center_offset(count, max, line_height))?;
+
+        for line in 0..max {
+            let text = lines.get(line as usize).copied().unwrap_or_default();
+            self.display(line, Command::String(text.to_string()))?;
+        }
+
+        Ok(())
     }
 
-    /// Get the current text color
+    /// Display key value pairs as a table.
     ///
-    /// Returns a RGB8 tuple with (red, green, blue). XOSD originally returns
-    /// RGB16 but since X11 RGB colors are defined as RGB8, it gets converted to
-    /// RGB8.
+    /// Every row is displayed on its own line starting at line 0. The value is
+    /// right-aligned by padding the row with spaces to `width_chars`
+    /// characters. This only lines up the values with a monospaced font like
+    /// `fixed`.
     ///
     /// # Errors
     ///
-    /// If `xosd_get_colour` fails the xosd error message is wrapped in a
-    /// [`Error::XosdError`] and returned. If the color conversion from u16 to u8
-    /// fails [`Error::TryFromIntError`] gets returned.
+    /// * If there are more rows than the XOSD window can display
+    ///   [`Error::TooManyLines`] is returned.
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
     ///
     /// # Example
     ///
     /// ```
     /// # use xosd_rs::Xosd;
-    /// let mut osd = Xosd::new(1)?;
+    /// let mut osd = Xosd::new(3)?;
     ///
-    /// assert_eq!(osd.color()?, (0, 255, 0));
-    ///
-    /// osd.set_color("LimeGreen")?;
+    /// osd.display_table(&[("CPU", "12%"), ("MEM", "48%"), ("NET", "1.2 MB/s")], 14)?;
     ///
-    /// assert_eq!(osd.color()?, (50, 205, 50));
+    /// // The display shows:
+    /// // CPU        12%
+    /// // MEM        48%
+    /// // NET   1.2 MB/s
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn color(&mut self) -> Result<(u8, u8, u8)> {
-        let mut red = 0;
-        let mut green = 0;
-        let mut blue = 0;
+    pub fn display_table(&mut self, rows: &[(&str, &str)], width_chars: usize) -> Result<()> {
+        self.check_line_count(rows.len())?;
 
-        wrap_unsafe!(xosd_get_colour(self.0, &mut red, &mut green, &mut blue))?;
+        for (line, (key, value)) in (0..).zip(rows) {
+            self.display(line, Command::String(table_row(key, value, width_chars)))?;
+        }
 
-        Ok((
-            (red / 256).try_into()?,
-            (green / 256).try_into()?,
-            (blue / 256).try_into()?,
-        ))
+        Ok(())
     }
 
-    /// Scroll the display
+    /// Display raw bytes in the given encoding.
+    ///
+    /// This is meant for programs that emit text in legacy encodings. `bytes`
+    /// is decoded with [`Encoding::decode`] and displayed as a
+    /// [`Command::String`].
     ///
-    /// Scrolls the display by a number of lines up
+    /// # Returns
+    ///
+    /// The number of characters written is returned.
     ///
     /// # Errors
     ///
-    /// * If `xosd_get_number_lines` fails the xosd error message is wrapped in a
-    /// [`Error::XosdError`] and returned.
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
     ///
     /// # Example
     ///
     /// ```
-    /// # use xosd_rs::{Xosd, Command};
-    /// let mut osd = Xosd::new(2)?;
+    /// # use xosd_rs::{Xosd, Encoding};
+    /// let mut osd = Xosd::new(1)?;
     ///
-    /// osd.display(0, Command::string("Hello,")?)?;
-    /// osd.display(1, Command::string("World!")?)?;
+    /// osd.display_bytes(0, b"Gr\xfc\xdfe", Encoding::Latin1)?;
     ///
-    /// // The display shows:
-    /// // Hello,
-    /// // World!
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn display_bytes(&mut self, line: i32, bytes: &[u8], encoding: Encoding) -> Result<u16> {
+        self.display(line, Command::String(encoding.decode(bytes).into_owned()))
+    }
+
+    /// Display a question with its possible answers until it is hidden.
     ///
-    /// osd.scroll(1)?;
+    /// The question is displayed on line 0 and the options joined by `" / "`
+    /// on line 1. The timeout is disabled so the prompt stays until
+    /// [`Xosd::hide`] is called. Reading the answer is up to the caller. The
+    /// XOSD window needs at least 2 lines.
+    ///
+    /// The timeout stays disabled after the prompt is hidden, so later
+    /// displays stay visible too. Save it with [`Xosd::timeout`] and restore
+    /// it with [`Xosd::set_timeout`] if needed, or use
+    /// [`Xosd::with_overrides`].
+    ///
+    /// # Errors
+    ///
+    /// * If the window has less than 2 lines [`Error::InvalidLine`] is returned.
+    /// * If any of the underlying xosd functions fails the xosd error message
+    ///   is wrapped in a [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(2)?;
+    ///
+    /// osd.set_timeout(3)?;
+    ///
+    /// osd.display_prompt("Suspend now?", &["Yes", "No"])?;
     ///
     /// // The display shows:
-    /// // World!
-    /// //
+    /// // Suspend now?
+    /// // Yes / No
+    ///
+    /// osd.hide()?;
+    /// osd.set_timeout(3)?;
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn scroll(&mut self, lines: i32) -> Result<()> {
-        wrap_unsafe!(xosd_scroll(self.0, lines))
+    pub fn display_prompt(&mut self, question: &str, options: &[&str]) -> Result<()> {
+        let max = self.max_lines()?;
+        if max < 2 {
+            return Err(Error::InvalidLine { line: 1, max });
+        }
+
+        self.set_raw_timeout(-1)?;
+        self.display(0, Command::String(question.to_string()))?;
+        self.display(1, Command::String(options.join(" / ")))?;
+
+        Ok(())
     }
 
-    /// Get the maximum number of lines that can be displayed on the XOSD window.
+    /// Reveal text character by character like a typewriter.
+    ///
+    /// Displays progressively longer prefixes of `text` on `line`, waiting
+    /// `per_char` between each of them, and ends on the full string. This
+    /// function blocks until the whole text is displayed. Like
+    /// [`Xosd::display`] a negative `line` counts from the last line.
     ///
     /// # Errors
     ///
-    /// * If `xosd_get_number_lines` fails the xosd error message is wrapped in a
-    /// [`Error::XosdError`] and returned.
+    /// * If `line` is not a line of the XOSD window [`Error::InvalidLine`] is
+    ///   returned.
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
     ///
     /// # Example
     ///
     /// ```
+    /// # use std::time::Duration;
     /// # use xosd_rs::Xosd;
-    /// assert_eq!(Xosd::new(1)?.max_lines()?, 1);
-    /// assert_eq!(Xosd::new(123)?.max_lines()?, 123);
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.type_out(0, "Incoming transmission", Duration::from_millis(5))?;
     ///
     /// # Ok::<(), xosd_rs::Error>(())
     /// ```
-    pub fn max_lines(&mut self) -> Result<i32> {
-        let res = unsafe { xosd_get_number_lines(self.0) };
+    pub fn type_out(&mut self, line: i32, text: &str, per_char: Duration) -> Result<()> {
+        let line = self.absolute_line(line)?;
+        self.check_line(line)?;
 
-        if res < 0 {
-            Err(Error::XosdError(error_str()?.into_owned()))
-        } else {
-            Ok(res.into())
+        for (index, prefix) in prefixes(text).enumerate() {
+            if index > 0 {
+                thread::sleep(per_char);
+            }
+
+            self.display(line, Command::String(prefix.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Reveal lines one at a time.
+    ///
+    /// Displays `lines` in order starting at line 0, waiting `per_line`
+    /// between each of them. Lines already revealed stay on the screen. This
+    /// function blocks until every line is displayed.
+    ///
+    /// # Errors
+    ///
+    /// * If there are more lines than the XOSD window can display
+    ///   [`Error::TooManyLines`] is returned.
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(3)?;
+    ///
+    /// osd.reveal_lines(
+    ///     &["Checking disks", "Checking network", "All good"],
+    ///     Duration::from_millis(5),
+    /// )?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn reveal_lines(&mut self, lines: &[&str], per_line: Duration) -> Result<()> {
+        self.check_line_count(lines.len())?;
+
+        for (line, text) in (0..).zip(lines) {
+            if line > 0 {
+                thread::sleep(per_line);
+            }
+
+            self.display(line, Command::String(text.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Display a countdown on a line.
+    ///
+    /// Displays every value from `from` down to 0 formatted with `format`,
+    /// waiting `interval` between each of them. This function blocks for
+    /// `from * interval`. Like [`Xosd::display`] a negative `line` counts from
+    /// the last line.
+    ///
+    /// # Errors
+    ///
+    /// * If `line` is not a line of the XOSD window [`Error::InvalidLine`] is
+    ///   returned.
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.countdown(0, 3, Duration::from_millis(5), |value| format!("Hiding in {}", value))?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn countdown<F>(
+        &mut self,
+        line: i32,
+        from: u32,
+        interval: Duration,
+        format: F,
+    ) -> Result<()>
+    where
+        F: Fn(u32) -> String,
+    {
+        let line = self.absolute_line(line)?;
+        self.check_line(line)?;
+
+        for value in (0..=from).rev() {
+            if value < from {
+                thread::sleep(interval);
+            }
+
+            self.display(line, Command::String(format(value)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Display a percentage bar that counts down on a line.
+    ///
+    /// The bar starts at `from` and decreases to 0 over `duration`. It is
+    /// updated every 40 milliseconds at most, but never more often than once
+    /// per percent. This function blocks for `duration`. Like
+    /// [`Xosd::display`] a negative `line` counts from the last line.
+    ///
+    /// # Errors
+    ///
+    /// * If `from` is greater than 100 [`Error::OutOfRangePercentage`] is
+    ///   returned.
+    /// * If `line` is not a line of the XOSD window [`Error::InvalidLine`] is
+    ///   returned.
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.display_countdown(0, 100, Duration::from_millis(200))?;
+    ///
+    /// assert_eq!(osd.current_value(0), Some(0));
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn display_countdown(&mut self, line: i32, from: u16, duration: Duration) -> Result<()> {
+        const STEP_INTERVAL: Duration = Duration::from_millis(40);
+
+        Command::percentage(from)?;
+        let line = self.absolute_line(line)?;
+        self.check_line(line)?;
+
+        let steps =
+            (duration.as_millis() / STEP_INTERVAL.as_millis()).clamp(1, from.max(1).into()) as u32;
+        let interval = duration / steps;
+
+        for step in 0..=steps {
+            if step > 0 {
+                thread::sleep(interval);
+            }
+
+            let value = u32::from(from) * (steps - step) / steps;
+            self.display(line, Command::Percentage(value.try_into()?))?;
+        }
+
+        Ok(())
+    }
+
+    /// Set the color the text fades to in [`Xosd::display_with_fade`].
+    ///
+    /// The XOSD window is transparent so the best fade target is the color
+    /// behind it. Defaults to black.
+    pub fn set_fade_background<C>(&mut self, color: C)
+    where
+        C: Into<Color>,
+    {
+        self.fade_background = color.into();
+    }
+
+    /// Set the color space used to fade in [`Xosd::display_with_fade`].
+    ///
+    /// Defaults to [`ColorSpace::Srgb`]. See [`ColorSpace`] for the
+    /// differences.
+    pub fn set_fade_color_space(&mut self, space: ColorSpace) {
+        self.fade_color_space = space;
+    }
+
+    /// Display data and fade it out before hiding the XOSD window.
+    ///
+    /// The data is displayed for `visible`. Afterwards the text color is
+    /// animated towards the color set with [`Xosd::set_fade_background`] over
+    /// `fade` in the color space set with [`Xosd::set_fade_color_space`] and
+    /// the XOSD window is hidden. The original text color is
+    /// restored once the window is hidden, even if fading failed. This
+    /// function blocks until the window is hidden.
+    ///
+    /// The colors of the fade are not cached and not passed to the callback
+    /// set with [`Xosd::set_on_color_change`].
+    ///
+    /// # Returns
+    ///
+    /// The same value [`Xosd::display`] returns.
+    ///
+    /// # Errors
+    ///
+    /// * If any of the underlying xosd functions fails the xosd error message
+    ///   is wrapped in a [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.display_with_fade(
+    ///     0,
+    ///     Command::string("Fading away")?,
+    ///     Duration::from_millis(20),
+    ///     Duration::from_millis(100),
+    /// )?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn display_with_fade(
+        &mut self,
+        line: i32,
+        command: Command,
+        visible: Duration,
+        fade: Duration,
+    ) -> Result<u16> {
+        let previous = self.state.color.clone();
+        let color = Color::from(self.color()?);
+        let res = self.display(line, command)?;
+
+        thread::sleep(visible);
+
+        let faded = self.fade_out(color, fade);
+        let restored = self.restore_color(previous);
+
+        faded?;
+        restored?;
+
+        Ok(res)
+    }
+
+    /// Fade the text color from `color` to the fade background and hide the
+    /// window, see [`Xosd::display_with_fade`].
+    fn fade_out(&mut self, color: Color, fade: Duration) -> Result<()> {
+        /// Time between two color changes while fading
+        const FADE_INTERVAL: Duration = Duration::from_millis(40);
+
+        let steps = (fade.as_millis() / FADE_INTERVAL.as_millis()).max(1) as u32;
+        for step in fade_steps(color, self.fade_background, steps, self.fade_color_space) {
+            self.set_raw_color(step)?;
+            thread::sleep(fade / steps);
+        }
+
+        if self.onscreen()? {
+            self.hide()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns wether the XOSD window is shown.
+    ///
+    /// Determines wether a XOSD window is currently beeing shown.
+    ///
+    /// Use [`Xosd::show`] and [`Xosd::hide`] to alter the visibility of the XOSD
+    /// window.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_is_onscreen` fails the xosd error message is wrapped in a
+    /// [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// assert_eq!(osd.onscreen()?, false);
+    ///
+    /// osd.show()?;
+    ///
+    /// assert_eq!(osd.onscreen()?, true);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn onscreen(&mut self) -> Result<bool> {
+        match call_xosd!(xosd_is_onscreen(self.handle)) {
+            1 => Ok(true),
+            0 => Ok(false),
+            _ => Err(last_error()),
+        }
+    }
+
+    /// Wait until the XOSD window is not displaying anything.
+    ///
+    /// Block until the timeout limit is reached and nothing is displayed on the XOSD window.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_wait_until_no_display` fails the xosd error message is wrapped in a
+    /// [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(1)?;
+    /// osd.set_timeout(1)?;
+    ///
+    /// osd.display(0, Command::string("Example XOSD output")?)?;
+    ///
+    /// if osd.onscreen()? {
+    ///     osd.wait_until_no_display()?;
+    /// }
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn wait_until_no_display(&mut self) -> Result<()> {
+        wrap_unsafe!(xosd_wait_until_no_display(self.handle))
+    }
+
+    /// Wait until the XOSD window is not displaying anything or `max` has
+    /// elapsed.
+    ///
+    /// Unlike [`Xosd::wait_until_no_display`] this polls [`Xosd::onscreen`]
+    /// every 50 milliseconds. Use
+    /// [`Xosd::wait_until_no_display_timeout_with_interval`] to poll at a
+    /// different interval.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the window is not shown anymore and `false` if `max` elapsed
+    /// first.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_is_onscreen` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.display(0, Command::string("Example XOSD output")?)?;
+    ///
+    /// // Without a timeout the window stays visible
+    /// assert!(!osd.wait_until_no_display_timeout(Duration::from_millis(100))?);
+    ///
+    /// osd.hide()?;
+    /// assert!(osd.wait_until_no_display_timeout(Duration::from_millis(100))?);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn wait_until_no_display_timeout(&mut self, max: Duration) -> Result<bool> {
+        self.wait_until_no_display_timeout_with_interval(max, Duration::from_millis(50))
+    }
+
+    /// Wait until the XOSD window is not displaying anything without
+    /// blocking the thread.
+    ///
+    /// This polls [`Xosd::onscreen`] every 50 milliseconds and sleeps with
+    /// [`tokio::time::sleep`] in between. Requires the `tokio` feature.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_is_onscreen` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, Command};
+    /// # async fn example() -> xosd_rs::Result<()> {
+    /// let mut osd = Xosd::new(1)?;
+    /// osd.set_timeout(1)?;
+    ///
+    /// osd.display(0, Command::string("Example XOSD output")?)?;
+    ///
+    /// osd.wait_until_no_display_async().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn wait_until_no_display_async(&mut self) -> Result<()> {
+        while self.onscreen()? {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        Ok(())
+    }
+
+    /// Like [`Xosd::wait_until_no_display_timeout`] but polls every `interval`
+    ///
+    /// # Errors
+    ///
+    /// See [`Xosd::wait_until_no_display_timeout`].
+    pub fn wait_until_no_display_timeout_with_interval(
+        &mut self,
+        max: Duration,
+        interval: Duration,
+    ) -> Result<bool> {
+        self.wait_for_onscreen(false, max, interval)
+    }
+
+    /// Wait until the XOSD window is displayed, but at most `max`.
+    ///
+    /// This is the counterpart of [`Xosd::wait_until_no_display_timeout`],
+    /// e.g. to wait for the window before taking a screenshot. It polls
+    /// [`Xosd::onscreen`] every 50 milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the window is shown and `false` if `max` elapsed first.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_is_onscreen` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// assert!(!osd.wait_until_displayed(Duration::from_millis(100))?);
+    ///
+    /// osd.display(0, Command::string("Example XOSD output")?)?;
+    /// assert!(osd.wait_until_displayed(Duration::from_millis(100))?);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn wait_until_displayed(&mut self, max: Duration) -> Result<bool> {
+        self.wait_for_onscreen(true, max, Duration::from_millis(50))
+    }
+
+    /// Poll [`Xosd::onscreen`] every `interval` until it returns `onscreen`.
+    ///
+    /// Returns `false` if `max` elapsed first.
+    fn wait_for_onscreen(
+        &mut self,
+        onscreen: bool,
+        max: Duration,
+        interval: Duration,
+    ) -> Result<bool> {
+        let deadline = Instant::now() + max;
+
+        loop {
+            if self.onscreen()? == onscreen {
+                return Ok(true);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(false);
+            }
+
+            thread::sleep(interval.min(deadline - now));
+        }
+    }
+
+    /// Display data and wait until it is not displayed anymore.
+    ///
+    /// This is a shortcut for [`Xosd::display`] followed by
+    /// [`Xosd::wait_until_no_display`] if the window is shown. Without a
+    /// timeout this blocks until the window is hidden from another thread.
+    ///
+    /// # Errors
+    ///
+    /// See [`Xosd::display`], [`Xosd::onscreen`] and
+    /// [`Xosd::wait_until_no_display`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(1)?;
+    /// osd.set_timeout(1)?;
+    ///
+    /// osd.display_blocking(0, Command::string("Example XOSD output")?)?;
+    ///
+    /// assert_eq!(osd.onscreen()?, false);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn display_blocking(&mut self, line: i32, command: Command) -> Result<()> {
+        self.display(line, command)?;
+
+        if self.onscreen()? {
+            self.wait_until_no_display()?;
+        }
+
+        Ok(())
+    }
+
+    /// Hide the XOSD window
+    ///
+    /// This unmaps the XOSD window. Use [`Xosd::show`] to remap it.
+    ///
+    /// If [`Xosd::display`] is used when the window is not visible, the window becomes visible again.
+    ///
+    /// xosd unmaps the window in its own thread, so this waits up to 100ms
+    /// until [`Xosd::onscreen`] reports the window as hidden.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_hide` fails the xosd error message is wrapped in a
+    /// [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// assert!(!osd.onscreen()?, "XOSD window initializes as hidden");
+    ///
+    /// osd.display(0, Command::string("Example XOSD output")?)?;
+    ///
+    /// assert!(osd.onscreen()?, "using Xosd::display shows the display");
+    ///
+    /// osd.hide()?;
+    ///
+    /// assert!(!osd.onscreen()?, "after using Xosd::hide the windows is not visible anymore");
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn hide(&mut self) -> Result<()> {
+        wrap_unsafe!(xosd_hide(self.handle))?;
+        self.settle(false)
+    }
+
+    /// Show the XOSD window
+    ///
+    /// Redisplay the data that has been previously displayed by [`Xosd::display`].
+    ///
+    /// xosd maps the window in its own thread, so this waits up to 100ms
+    /// until [`Xosd::onscreen`] reports the window as visible.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_show` fails the xosd error message is wrapped in a
+    /// [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// assert!(!osd.onscreen()?, "XOSD window initializes as hidden");
+    ///
+    /// osd.display(0, Command::string("Example XOSD output")?)?;
+    ///
+    /// assert!(osd.onscreen()?, "using Xosd::display shows the display");
+    ///
+    /// osd.hide()?;
+    ///
+    /// assert!(!osd.onscreen()?, "after using Xosd::hide the windows is not visible anymore");
+    ///
+    /// osd.show()?;
+    ///
+    /// assert!(osd.onscreen()?, "after using Xosd::show the windows is visible again");
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn show(&mut self) -> Result<()> {
+        wrap_unsafe!(xosd_show(self.handle))?;
+        self.settle(true)
+    }
+
+    /// Wait for the event thread of xosd to map or unmap the window.
+    ///
+    /// `xosd_is_onscreen` reports the old state until the event thread handled
+    /// `xosd_show` or `xosd_hide`. The mock changes the state immediately.
+    fn settle(&mut self, onscreen: bool) -> Result<()> {
+        if cfg!(not(feature = "mock")) {
+            let max = Duration::from_millis(100);
+            self.wait_for_onscreen(onscreen, max, Duration::from_millis(1))?;
+        }
+
+        Ok(())
+    }
+
+    /// Flash a command on a line
+    ///
+    /// Displays `command` on `line` and then shows and hides the window
+    /// `times` times, blocking for `interval` after every toggle. The window
+    /// is hidden when this returns. If `times` is 0 nothing is displayed.
+    ///
+    /// # Errors
+    ///
+    /// * See [`Xosd::display`], [`Xosd::show`], [`Xosd::hide`] and
+    ///   [`Xosd::onscreen`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.flash(0, Command::string("Low battery")?, 3, Duration::from_millis(300))?;
+    ///
+    /// assert!(!osd.onscreen()?);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn flash(
+        &mut self,
+        line: i32,
+        command: Command,
+        times: u32,
+        interval: Duration,
+    ) -> Result<()> {
+        if times == 0 {
+            return Ok(());
+        }
+
+        self.display(line, command)?;
+
+        for flash in 0..times {
+            // Displaying the command already showed the window
+            if flash > 0 && !self.onscreen()? {
+                self.show()?;
+            }
+            thread::sleep(interval);
+
+            // The timeout may have hidden the window already
+            if self.onscreen()? {
+                self.hide()?;
+            }
+            if flash + 1 < times {
+                thread::sleep(interval);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Change the vertical alignment of the XOSD window
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_set_pos` fails the xosd error message is wrapped in a
+    /// [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, VerticalAlign};
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_vertical_align(VerticalAlign::Top)?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_vertical_align(&mut self, align: VerticalAlign) -> Result<()> {
+        wrap_unsafe!(xosd_set_pos(self.handle, align.into()))?;
+        self.state.vertical_align = align;
+
+        Ok(())
+    }
+
+    /// Change the horizontal alignment of the XOSD window
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_set_align` fails the xosd error message is wrapped in a
+    /// [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, HorizontalAlign};
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// for align in &[
+    ///     HorizontalAlign::Left,
+    ///     HorizontalAlign::Center,
+    ///     HorizontalAlign::Right,
+    /// ] {
+    ///     osd.set_horizontal_align(*align)?;
+    /// }
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_horizontal_align(&mut self, align: HorizontalAlign) -> Result<()> {
+        wrap_unsafe!(xosd_set_align(self.handle, align.into()))?;
+        self.state.horizontal_align = align;
+
+        Ok(())
+    }
+
+    /// Get the vertical alignment of the XOSD window
+    ///
+    /// xosd has no getter for the alignment. This returns the alignment last
+    /// set with [`Xosd::set_vertical_align`] or xosd's default
+    /// [`VerticalAlign::Top`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, VerticalAlign};
+    /// let mut osd = Xosd::new(1)?;
+    /// assert_eq!(osd.vertical_align(), VerticalAlign::Top);
+    ///
+    /// osd.set_vertical_align(VerticalAlign::Bottom)?;
+    /// assert_eq!(osd.vertical_align(), VerticalAlign::Bottom);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn vertical_align(&self) -> VerticalAlign {
+        self.state.vertical_align
+    }
+
+    /// Get the horizontal alignment of the XOSD window
+    ///
+    /// xosd has no getter for the alignment. This returns the alignment last
+    /// set with [`Xosd::set_horizontal_align`] or xosd's default
+    /// [`HorizontalAlign::Left`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, HorizontalAlign};
+    /// let mut osd = Xosd::new(1)?;
+    /// assert_eq!(osd.horizontal_align(), HorizontalAlign::Left);
+    ///
+    /// osd.set_horizontal_align(HorizontalAlign::Right)?;
+    /// assert_eq!(osd.horizontal_align(), HorizontalAlign::Right);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn horizontal_align(&self) -> HorizontalAlign {
+        self.state.horizontal_align
+    }
+
+    /// Change the shadow offset of the XOSD window
+    ///
+    /// XOSD draws a shadow beneath the main XOSD window to increase readability.
+    ///
+    /// Change the size of the XOSD window by altering how many pixels the shadow
+    /// is offset to the bottom-right. One to four pixels result in a good
+    /// effect.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_set_shadow_offset` fails the xosd error message is wrapped in
+    /// a [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_shadow_offset(2)?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_shadow_offset(&mut self, offset: i32) -> Result<()> {
+        wrap_unsafe!(xosd_set_shadow_offset(self.handle, offset))?;
+        self.state.shadow_offset = offset;
+
+        Ok(())
+    }
+
+    /// Get the shadow offset of the XOSD window
+    ///
+    /// xosd has no getter for the shadow offset. This returns the offset last
+    /// set with [`Xosd::set_shadow_offset`] or xosd's default of 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    /// assert_eq!(osd.shadow_offset(), 0);
+    ///
+    /// osd.set_shadow_offset(osd.shadow_offset() + 1)?;
+    /// assert_eq!(osd.shadow_offset(), 1);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn shadow_offset(&self) -> i32 {
+        self.state.shadow_offset
+    }
+
+    /// Change the outline offset of the text
+    ///
+    /// XOSD draws a outline around the text on the XOSD window.
+    ///
+    /// Change the thickness of the outline in pixels.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_set_outline_offset` fails the xosd error message is wrapped in
+    /// a [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_outline_offset(2)?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_outline_offset(&mut self, offset: i32) -> Result<()> {
+        wrap_unsafe!(xosd_set_outline_offset(self.handle, offset))?;
+        self.state.outline_offset = offset;
+
+        Ok(())
+    }
+
+    /// Get the outline offset of the XOSD window
+    ///
+    /// xosd has no getter for the outline offset. This returns the offset last
+    /// set with [`Xosd::set_outline_offset`] or xosd's default of 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    /// assert_eq!(osd.outline_offset(), 0);
+    ///
+    /// osd.set_outline_offset(osd.outline_offset() + 1)?;
+    /// assert_eq!(osd.outline_offset(), 1);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn outline_offset(&self) -> i32 {
+        self.state.outline_offset
+    }
+
+    /// Set the shadow color of the XOSD window
+    ///
+    /// Change the color to one defined by X11 in
+    /// [`rgb.txt`](https://gitlab.freedesktop.org/xorg/app/rgb/raw/master/rgb.txt).
+    /// A [`Color`] is passed as `#rrggbb`.
+    ///
+    /// # Errors
+    ///
+    /// * If `color` contains control characters [`Error::InvalidColor`] is
+    ///   returned.
+    /// * If `color` contains a NUL byte [`Error::InvalidString`] is returned.
+    /// * If `xosd_set_shadow_colour` fails the xosd error message is wrapped in
+    /// a [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_shadow_color("White")?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_shadow_color<S>(&mut self, color: S) -> Result<()>
+    where
+        S: Into<Vec<u8>>,
+    {
+        let color = checked_cstring(color, "shadow color", Error::InvalidColor)?;
+        debug!("set shadow color to {:?}", color);
+
+        wrap_unsafe!(xosd_set_shadow_colour(self.handle, color.as_ptr()))?;
+        self.state.shadow_color = Some(color.to_string_lossy().into_owned());
+
+        Ok(())
+    }
+
+    /// Set the outline color of the text
+    ///
+    /// Change the color to one defined by X11 in
+    /// [`rgb.txt`](https://gitlab.freedesktop.org/xorg/app/rgb/raw/master/rgb.txt).
+    /// A [`Color`] is passed as `#rrggbb`.
+    ///
+    /// # Errors
+    ///
+    /// * If `color` contains control characters [`Error::InvalidColor`] is
+    ///   returned.
+    /// * If `color` contains a NUL byte [`Error::InvalidString`] is returned.
+    /// * If `xosd_set_outline_colour` fails the xosd error message is wrapped in
+    /// a [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_outline_color("Grey")?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_outline_color<S>(&mut self, color: S) -> Result<()>
+    where
+        S: Into<Vec<u8>>,
+    {
+        let color = checked_cstring(color, "outline color", Error::InvalidColor)?;
+        debug!("set outline color to {:?}", color);
+
+        wrap_unsafe!(xosd_set_outline_colour(self.handle, color.as_ptr()))?;
+        self.state.outline_color = Some(color.to_string_lossy().into_owned());
+
+        Ok(())
+    }
+
+    /// Change the horizontal offset of the XOSD window
+    ///
+    /// Changes the number of pixels the XOSD window is offset from left of the
+    /// screen.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_set_horizontal_offset` fails the xosd error message is wrapped in
+    /// a [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_horizontal_offset(20)?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_horizontal_offset(&mut self, offset: i32) -> Result<()> {
+        wrap_unsafe!(xosd_set_horizontal_offset(self.handle, offset))?;
+        self.state.horizontal_offset = offset;
+
+        Ok(())
+    }
+
+    /// Change the vertical offset of the XOSD window
+    ///
+    /// Changes the number of pixels the XOSD window is offset from the top or
+    /// bottom of the screen. Set this to 48 to avoid desktop-panels like in
+    /// GNOME or KDE.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_set_vertical_offset` fails the xosd error message is wrapped in
+    /// a [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_vertical_offset(48)?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_vertical_offset(&mut self, offset: i32) -> Result<()> {
+        wrap_unsafe!(xosd_set_vertical_offset(self.handle, offset))?;
+        self.state.vertical_offset = offset;
+
+        Ok(())
+    }
+
+    /// Reset every setting to the xosd default
+    ///
+    /// These settings are restored, the displayed lines are kept:
+    ///
+    /// * the font [`default_font`] and the color [`default_color`]
+    /// * black shadow and outline colors
+    /// * no timeout and the default bar length
+    /// * [`VerticalAlign::Top`] and [`HorizontalAlign::Left`]
+    /// * 0 for every offset
+    ///
+    /// Afterwards [`Xosd::snapshot`] reports the settings as never changed.
+    ///
+    /// # Errors
+    ///
+    /// * If `osd_default_font` or `osd_default_colour` points to NULL
+    ///   [`Error::IsNullPtr`] is returned.
+    /// * If any of the underlying xosd functions fails the xosd error message
+    ///   is wrapped in a [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, VerticalAlign};
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_color("red")?;
+    /// osd.set_vertical_align(VerticalAlign::Bottom)?;
+    ///
+    /// osd.reset()?;
+    ///
+    /// assert_eq!(osd.color()?, (0, 255, 0));
+    /// assert_eq!(osd.vertical_align(), VerticalAlign::Top);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn reset(&mut self) -> Result<()> {
+        self.apply_settings(&Snapshot::new(0))
+    }
+
+    /// Change settings for the duration of `body`
+    ///
+    /// `overrides` changes the settings, then `body` runs with the changed
+    /// settings. Afterwards every setting is restored to what it was before
+    /// `overrides` ran, even if `overrides` or `body` failed. The displayed
+    /// lines are not restored.
+    ///
+    /// # Errors
+    ///
+    /// * If `overrides` or `body` fails its error is returned after the
+    ///   settings were restored.
+    /// * If restoring the settings fails the error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    /// osd.set_color("white")?;
+    ///
+    /// osd.with_overrides(
+    ///     |osd| osd.set_color("red"),
+    ///     |osd| {
+    ///         osd.display_string(0, "Battery low")?;
+    ///         assert_eq!(osd.color()?, (255, 0, 0));
+    ///         Ok(())
+    ///     },
+    /// )?;
+    ///
+    /// assert_eq!(osd.color()?, (255, 255, 255));
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn with_overrides<F, B, R>(&mut self, overrides: F, body: B) -> Result<R>
+    where
+        F: FnOnce(&mut Xosd) -> Result<()>,
+        B: FnOnce(&mut Xosd) -> Result<R>,
+    {
+        let previous = self.state.clone();
+
+        let res = overrides(self).and_then(|()| body(self));
+        let restored = self.apply_settings(&previous);
+
+        let value = res?;
+        restored?;
+
+        Ok(value)
+    }
+
+    /// Change several settings in a chain
+    ///
+    /// See [`Configurator`] for the settings that can be changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.configure().font("fixed")?.color("green")?.timeout(3)?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn configure(&mut self) -> Configurator<'_> {
+        Configurator::new(self)
+    }
+
+    /// Change the alignment and offsets of the XOSD window at once
+    ///
+    /// Calls [`Xosd::set_vertical_align`], [`Xosd::set_horizontal_align`],
+    /// [`Xosd::set_vertical_offset`] and [`Xosd::set_horizontal_offset`] in
+    /// this order. If one of them fails the previous position is restored, so
+    /// the window is not left half positioned.
+    ///
+    /// # Errors
+    ///
+    /// * If one of the xosd functions fails the xosd error message is wrapped
+    ///   in a [`Error::XosdError`] and returned. The message names the
+    ///   function that failed, e.g. `[during set_vertical_offset] ...`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, HorizontalAlign, VerticalAlign};
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// // Above a panel in the bottom right corner
+    /// osd.set_position(VerticalAlign::Bottom, HorizontalAlign::Right, 48, 10)?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_position(
+        &mut self,
+        vertical_align: VerticalAlign,
+        horizontal_align: HorizontalAlign,
+        vertical_offset: i32,
+        horizontal_offset: i32,
+    ) -> Result<()> {
+        let previous = self.snapshot();
+
+        let res = self.apply_position(
+            vertical_align,
+            horizontal_align,
+            vertical_offset,
+            horizontal_offset,
+        );
+        if res.is_err() {
+            // Restoring is best effort, the first error is returned
+            let _ = self.apply_position(
+                previous.vertical_align,
+                previous.horizontal_align,
+                previous.vertical_offset,
+                previous.horizontal_offset,
+            );
+        }
+
+        res
+    }
+
+    fn apply_position(
+        &mut self,
+        vertical_align: VerticalAlign,
+        horizontal_align: HorizontalAlign,
+        vertical_offset: i32,
+        horizontal_offset: i32,
+    ) -> Result<()> {
+        self.set_vertical_align(vertical_align)?;
+        self.set_horizontal_align(horizontal_align)?;
+        self.set_vertical_offset(vertical_offset)?;
+        self.set_horizontal_offset(horizontal_offset)
+    }
+
+    /// Change the time until the XOSD window is hidden.
+    ///
+    /// Changes the number of seconds to wait after displaying data to hide the
+    /// XOSD window.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_set_timeout` fails the xosd error message is wrapped in
+    /// a [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_timeout(3)?;
+    ///
+    /// osd.display(0, Command::string("Test")?)?;
+    ///
+    /// if osd.onscreen()? {
+    ///     osd.wait_until_no_display()?;
+    /// }
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_timeout(&mut self, timeout: u16) -> Result<()> {
+        self.set_raw_timeout(timeout.into())
+    }
+
+    /// Change the time until the XOSD window is hidden to a [`Duration`].
+    ///
+    /// Works like [`Xosd::set_timeout`] but takes a [`Duration`]. xosd only
+    /// supports whole seconds, so `timeout` is rounded up: a sub-second
+    /// timeout becomes 1 second.
+    ///
+    /// # Errors
+    ///
+    /// * If `timeout` has more seconds than fit into an `i32`
+    ///   [`Error::TryFromIntError`] is returned.
+    /// * If `xosd_set_timeout` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// // Rounded up to 2 seconds
+    /// osd.set_timeout_duration(Duration::from_millis(1500))?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_timeout_duration(&mut self, timeout: Duration) -> Result<()> {
+        self.set_raw_timeout(timeout_seconds(timeout)?)
+    }
+
+    /// Never hide the XOSD window automatically.
+    ///
+    /// This sets the xosd timeout to `-1`, which is also the timeout of a new
+    /// XOSD window. The window stays visible until it is hidden with
+    /// [`Xosd::hide`]. Use [`Xosd::set_timeout`] to hide it after a timeout
+    /// again.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_set_timeout` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_timeout(3)?;
+    /// osd.set_no_timeout()?;
+    ///
+    /// osd.display_string(0, "Always on top")?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_no_timeout(&mut self) -> Result<()> {
+        self.set_raw_timeout(-1)
+    }
+
+    /// Get the timeout of the XOSD window in seconds
+    ///
+    /// xosd has no getter for the timeout. This returns the timeout last set
+    /// with [`Xosd::set_timeout`] or one of the other timeout setters.
+    ///
+    /// # Errors
+    ///
+    /// * If the window has no timeout, which is the default,
+    ///   [`Error::TryFromIntError`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    /// assert!(osd.timeout().is_err());
+    ///
+    /// osd.set_timeout(3)?;
+    /// osd.set_timeout(osd.timeout()? + 2)?;
+    ///
+    /// assert_eq!(osd.timeout()?, 5);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn timeout(&self) -> Result<u16> {
+        Ok(self.state.timeout.try_into()?)
+    }
+
+    fn set_raw_timeout(&mut self, timeout: i32) -> Result<()> {
+        debug!("set timeout to {}", timeout);
+        wrap_unsafe!(xosd_set_timeout(self.handle, timeout))?;
+        self.state.timeout = timeout;
+        self.burst_timeout = None;
+
+        Ok(())
+    }
+
+    /// Keep the XOSD window visible during a burst of updates.
+    ///
+    /// In a fast update loop the window can be hidden by the timeout between
+    /// two updates and flicker when the next update shows it again. This
+    /// suppresses the timeout until [`Xosd::end_burst`] is called, so the
+    /// window stays visible for the whole burst.
+    ///
+    /// Calling this during a burst does nothing. Changing the timeout during
+    /// a burst ends the burst.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_set_timeout` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(1)?;
+    /// osd.set_timeout(1)?;
+    ///
+    /// osd.begin_burst()?;
+    /// for volume in (5..=50).step_by(5) {
+    ///     osd.display(0, Command::percentage(volume)?)?;
+    /// }
+    /// osd.end_burst()?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn begin_burst(&mut self) -> Result<()> {
+        if self.burst_timeout.is_none() {
+            let timeout = self.state.timeout;
+
+            self.set_raw_timeout(-1)?;
+            self.burst_timeout = Some(timeout);
+        }
+
+        Ok(())
+    }
+
+    /// End a burst started with [`Xosd::begin_burst`].
+    ///
+    /// Restores the timeout that was set before the burst. Calling this
+    /// outside of a burst does nothing.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_set_timeout` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    pub fn end_burst(&mut self) -> Result<()> {
+        match self.burst_timeout {
+            Some(timeout) => self.set_raw_timeout(timeout),
+            None => Ok(()),
+        }
+    }
+
+    /// Change the text color
+    ///
+    /// Change the color to one defined by X11 in
+    /// [`rgb.txt`](https://gitlab.freedesktop.org/xorg/app/rgb/raw/master/rgb.txt).
+    /// A [`Color`] is passed as `#rrggbb`.
+    ///
+    /// # Errors
+    ///
+    /// * If `color` contains control characters [`Error::InvalidColor`] is
+    ///   returned.
+    /// * If `color` contains a NUL byte [`Error::InvalidString`] is returned.
+    /// * If `xosd_set_colour` fails the xosd error message is wrapped in a
+    /// [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_color("LimeGreen")?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_color<S>(&mut self, color: S) -> Result<()>
+    where
+        S: Into<Vec<u8>>,
+    {
+        let color = self.set_raw_color(color)?;
+        self.state.color = Some(color.to_string_lossy().into_owned());
+
+        if let Some(callback) = self.on_color_change.clone() {
+            callback(Color::from(self.color()?));
+        }
+
+        Ok(())
+    }
+
+    /// Change the text color with full precision
+    ///
+    /// The color is passed to [`Xosd::set_color`] as `#rrrrggggbbbb`, so the
+    /// 16 bit channels are kept. Use [`Xosd::color16`] to read them back.
+    ///
+    /// # Errors
+    ///
+    /// See [`Xosd::set_color`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_color_rgb16(0x1234, 0xabcd, 0x0f0f)?;
+    ///
+    /// assert_eq!(osd.color16()?, (0x1234, 0xabcd, 0x0f0f));
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_color_rgb16(&mut self, red: u16, green: u16, blue: u16) -> Result<()> {
+        self.set_color(format!("#{:04x}{:04x}{:04x}", red, green, blue))
+    }
+
+    /// Set a callback that is called whenever the text color changes.
+    ///
+    /// The callback is called with the new color after [`Xosd::set_color`] or
+    /// any helper built on top of it succeeded. The temporary colors of
+    /// [`Xosd::display_with_fade`] and [`Xosd::display_bar_colored`] are not
+    /// reported. Changes made to the XOSD window outside of this [`Xosd`]
+    /// object are not detected.
+    ///
+    /// Setting a new callback replaces the previous one.
+    ///
+    /// # Errors
+    ///
+    /// Once a callback is set, [`Xosd::set_color`] reads the new color back
+    /// and fails like [`Xosd::color`] if that is not possible.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_on_color_change(|color| println!("The text color is now {}", color));
+    ///
+    /// // Prints: The text color is now #32cd32
+    /// osd.set_color("LimeGreen")?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_on_color_change<F>(&mut self, callback: F)
+    where
+        F: Fn(Color) + Send + Sync + 'static,
+    {
+        self.on_color_change = Some(Arc::new(callback));
+    }
+
+    /// Remove the callback set with [`Xosd::set_on_color_change`].
+    pub fn clear_on_color_change(&mut self) {
+        self.on_color_change = None;
+    }
+
+    /// Change the text color to a color of a palette
+    ///
+    /// This function is only available with the `palettes` feature.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_set_colour` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, Palette, Role};
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.apply_palette_color(&Palette::NORD, Role::Accent)?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    #[cfg(feature = "palettes")]
+    pub fn apply_palette_color(&mut self, palette: &Palette, role: Role) -> Result<()> {
+        self.set_color(palette.color(role))
+    }
+
+    /// Change the text font
+    ///
+    /// Changes the font used to render text on the XOSD window. A X11 font
+    /// description name must be passed.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// * If `font` contains control characters [`Error::InvalidFont`] is
+    ///   returned.
+    /// * If `font` contains a NUL byte [`Error::InvalidString`] is returned.
+    /// * If `xosd_set_font` fails the xosd error message is wrapped in a
+    /// [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_font("fixed")?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_font<S>(&mut self, font: S) -> Result<()>
+    where
+        S: Into<Vec<u8>>,
+    {
+        let font = checked_cstring(font, "font", Error::InvalidFont)?;
+        debug!("set font to {:?}", font);
+
+        wrap_unsafe!(xosd_set_font(self.handle, font.as_ptr()))?;
+        self.state.font = Some(font.to_string_lossy().into_owned());
+
+        Ok(())
+    }
+
+    /// Change the text font and report fonts that can't be loaded
+    ///
+    /// Works like [`Xosd::set_font`], but returns [`Error::FontNotFound`]
+    /// instead of a [`Error::XosdError`] when xosd rejects the font.
+    ///
+    /// xosd has no getter for the font it actually uses. If the X server
+    /// substitutes a similar font or loads it without some character sets,
+    /// xosd reports success and so does this function. Only fonts xosd
+    /// rejects are detected.
+    ///
+    /// # Errors
+    ///
+    /// * If `font` contains control characters [`Error::InvalidFont`] is
+    ///   returned.
+    /// * If `font` contains a NUL byte [`Error::InvalidString`] is returned.
+    /// * If xosd can't load `font` [`Error::FontNotFound`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Error, Xosd};
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_font_strict("fixed")?;
+    ///
+    /// assert_eq!(
+    ///     osd.set_font_strict("no such font"),
+    ///     Err(Error::FontNotFound("no such font".to_string()))
+    /// );
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_font_strict(&mut self, font: &str) -> Result<()> {
+        match self.set_font(font) {
+            Err(Error::XosdError(_)) => Err(Error::FontNotFound(font.to_string())),
+            res => res,
+        }
+    }
+
+    /// Check whether the X server can load a font
+    ///
+    /// xosd does not expose its X display, so the fonts of the X server can't
+    /// be listed. Instead this tries to set `font` and switches back to the
+    /// previous font, or the xosd default font if none was set.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the font can be loaded and `false` if xosd rejects it.
+    ///
+    /// # Errors
+    ///
+    /// * If `font` contains control characters [`Error::InvalidFont`] is
+    ///   returned.
+    /// * If `font` contains a NUL byte [`Error::InvalidString`] is returned.
+    /// * If switching back to the previous font fails the xosd error message is
+    ///   wrapped in a [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// assert!(osd.validate_font("fixed")?);
+    /// assert!(!osd.validate_font("no such font")?);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn validate_font(&mut self, font: &str) -> Result<bool> {
+        let previous = self.state.font.clone();
+
+        match self.set_font(font) {
+            Ok(()) => {}
+            Err(Error::XosdError(_)) => return Ok(false),
+            Err(err) => return Err(err),
+        }
+
+        match &previous {
+            Some(font) => self.set_font(font.as_str())?,
+            None => self.set_font(default_font()?.into_owned())?,
+        }
+        self.state.font = previous;
+
+        Ok(true)
+    }
+
+    /// Get the current text color
+    ///
+    /// Returns a RGB8 tuple with (red, green, blue). XOSD originally returns
+    /// RGB16 but since X11 RGB colors are defined as RGB8, it gets converted to
+    /// RGB8 by scaling every channel from `0..=65535` to `0..=255`.
+    ///
+    /// # Errors
+    ///
+    /// If `xosd_get_colour` fails the xosd error message is wrapped in a
+    /// [`Error::XosdError`] and returned. If the color conversion from u16 to u8
+    /// fails [`Error::TryFromIntError`] gets returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// assert_eq!(osd.color()?, (0, 255, 0));
+    ///
+    /// osd.set_color("LimeGreen")?;
+    ///
+    /// assert_eq!(osd.color()?, (50, 205, 50));
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn color(&mut self) -> Result<(u8, u8, u8)> {
+        Ok(rgb16_to_rgb8(self.color16()?))
+    }
+
+    /// Get the current outline color
+    ///
+    /// Returns a RGB8 tuple with (red, green, blue) converted like
+    /// [`Xosd::color`]. The XOSD library can't report the outline color, so
+    /// the color set with [`Xosd::set_outline_color`] is looked up in a copy
+    /// of X11's `rgb.txt`. Without a color set, the xosd default black is
+    /// returned.
+    ///
+    /// # Errors
+    ///
+    /// * If the outline color is neither a name from `rgb.txt` nor a hex
+    ///   color like `#32cd32` [`Error::InvalidColor`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// assert_eq!(osd.outline_color()?, (0, 0, 0));
+    ///
+    /// osd.set_outline_color("LimeGreen")?;
+    ///
+    /// assert_eq!(osd.outline_color()?, (50, 205, 50));
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn outline_color(&mut self) -> Result<(u8, u8, u8)> {
+        cached_color(self.state.outline_color.as_deref())
+    }
+
+    /// Get the current shadow color
+    ///
+    /// Returns a RGB8 tuple with (red, green, blue) converted like
+    /// [`Xosd::color`]. The XOSD library can't report the shadow color, so
+    /// the color set with [`Xosd::set_shadow_color`] is looked up in a copy
+    /// of X11's `rgb.txt`. Without a color set, the xosd default black is
+    /// returned.
+    ///
+    /// # Errors
+    ///
+    /// * If the shadow color is neither a name from `rgb.txt` nor a hex color
+    ///   like `#32cd32` [`Error::InvalidColor`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// assert_eq!(osd.shadow_color()?, (0, 0, 0));
+    ///
+    /// osd.set_shadow_color("White")?;
+    ///
+    /// assert_eq!(osd.shadow_color()?, (255, 255, 255));
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn shadow_color(&mut self) -> Result<(u8, u8, u8)> {
+        cached_color(self.state.shadow_color.as_deref())
+    }
+
+    /// Get the current text font
+    ///
+    /// The XOSD library can't report the font, so this returns the font last
+    /// set with [`Xosd::set_font`]. Without a font set, [`default_font`] is
+    /// returned.
+    ///
+    /// # Errors
+    ///
+    /// * If no font was set and `osd_default_font` points to NULL
+    ///   [`Error::IsNullPtr`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{default_font, Xosd};
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// assert_eq!(osd.font()?, default_font()?);
+    ///
+    /// osd.set_font("fixed")?;
+    ///
+    /// assert_eq!(osd.font()?, "fixed");
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn font(&mut self) -> Result<Cow<'_, str>> {
+        match &self.state.font {
+            Some(font) => Ok(Cow::Borrowed(font)),
+            None => default_font(),
+        }
+    }
+
+    /// Get the current text color with full precision
+    ///
+    /// Returns the RGB16 tuple with (red, green, blue) as XOSD stores it. Use
+    /// [`Xosd::color`] to get the color as RGB8.
+    ///
+    /// # Errors
+    ///
+    /// If `xosd_get_colour` fails the xosd error message is wrapped in a
+    /// [`Error::XosdError`] and returned. If a channel does not fit into an
+    /// `u16` [`Error::TryFromIntError`] gets returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_color("LimeGreen")?;
+    ///
+    /// assert_eq!(osd.color16()?, (12850, 52685, 12850));
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn color16(&mut self) -> Result<(u16, u16, u16)> {
+        let mut red = 0;
+        let mut green = 0;
+        let mut blue = 0;
+
+        wrap_unsafe!(xosd_get_colour(
+            self.handle,
+            &mut red,
+            &mut green,
+            &mut blue
+        ))?;
+
+        Ok((red.try_into()?, green.try_into()?, blue.try_into()?))
+    }
+
+    /// Scroll the display
+    ///
+    /// Scrolls the display by a number of lines up. xosd can only scroll up,
+    /// use [`Xosd::scroll_down`] to scroll in the other direction.
+    ///
+    /// # Errors
+    ///
+    /// * If `lines` is negative or greater than the maximum number of lines,
+    ///   or if `xosd_scroll` fails, the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(2)?;
+    ///
+    /// osd.display(0, Command::string("Hello,")?)?;
+    /// osd.display(1, Command::string("World!")?)?;
+    ///
+    /// // The display shows:
+    /// // Hello,
+    /// // World!
+    ///
+    /// osd.scroll(1)?;
+    ///
+    /// // The display shows:
+    /// // World!
+    /// //
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn scroll(&mut self, lines: i32) -> Result<()> {
+        wrap_unsafe!(xosd_scroll(self.handle, lines))?;
+
+        let lines = usize::try_from(lines)
+            .unwrap_or_default()
+            .min(self.line_deadlines.len());
+        self.state.scroll(lines);
+        self.line_deadlines.drain(..lines);
+        self.line_deadlines.extend(iter::repeat_n(None, lines));
+
+        Ok(())
+    }
+
+    /// Scroll the display up and return how far it was scrolled
+    ///
+    /// Like [`Xosd::scroll`] but `lines` is limited to the last line that
+    /// shows something, so scrolling never goes past the content. Lines
+    /// showing an empty string count as empty.
+    ///
+    /// # Returns
+    ///
+    /// The number of lines actually scrolled. This is 0 once nothing is
+    /// displayed anymore.
+    ///
+    /// # Errors
+    ///
+    /// See [`Xosd::scroll`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(3)?;
+    ///
+    /// osd.display_string(0, "Hello,")?;
+    /// osd.display_string(1, "World!")?;
+    ///
+    /// assert_eq!(osd.scroll_checked(5)?, 2);
+    /// assert_eq!(osd.scroll_checked(1)?, 0);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn scroll_checked(&mut self, lines: i32) -> Result<i32> {
+        let content = self
+            .state
+            .lines
+            .iter()
+            .rposition(|command| match command {
+                Some(Command::String(string)) => !string.is_empty(),
+                Some(_) => true,
+                None => false,
+            })
+            .map_or(0, |index| index + 1);
+
+        let lines = lines.min(content.try_into()?);
+        self.scroll(lines)?;
+
+        Ok(lines)
+    }
+
+    /// Scroll the display down
+    ///
+    /// Scrolls the display by a number of lines down and inserts blank lines
+    /// at the top. Lines that are moved past the last line are discarded.
+    ///
+    /// xosd can only scroll up, so every line is displayed again from the
+    /// cached content. Like [`Xosd::display`] this shows the window.
+    ///
+    /// # Errors
+    ///
+    /// * If `lines` is negative [`Error::TryFromIntError`] is returned.
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(2)?;
+    ///
+    /// osd.display(0, Command::string("Hello,")?)?;
+    /// osd.display(1, Command::string("World!")?)?;
+    ///
+    /// osd.scroll_down(1)?;
+    ///
+    /// // The display shows:
+    /// //
+    /// // Hello,
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn scroll_down(&mut self, lines: i32) -> Result<()> {
+        let lines = usize::try_from(lines)?;
+
+        let mut state = self.state.clone();
+        state.scroll_down(lines);
+
+        let max = self.line_deadlines.len();
+        let mut deadlines = self.line_deadlines.clone();
+        deadlines.splice(..0, iter::repeat_n(None, lines.min(max)));
+        deadlines.truncate(max);
+
+        for (line, command) in (0..).zip(&state.lines) {
+            let command = command
+                .clone()
+                .unwrap_or_else(|| Command::String(String::new()));
+            self.display_raw(line, command)?;
+        }
+
+        self.state.lines = state.lines;
+        self.line_deadlines = deadlines;
+
+        Ok(())
+    }
+
+    /// Display the data shown on every line again.
+    ///
+    /// A font or color change only becomes visible once data is displayed
+    /// again. This displays the data cached for [`Xosd::snapshot`] on every
+    /// line, so the change takes effect at once. The text transform is not
+    /// applied again and lines displayed with [`Xosd::display_line_for`] are
+    /// still cleared on time.
+    ///
+    /// Like [`Xosd::display`] this shows the window if it was hidden.
+    /// `refresh` does nothing if no data was displayed yet.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.display_string(0, "Dark mode")?;
+    ///
+    /// osd.set_color("White")?;
+    /// osd.refresh()?;
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn refresh(&mut self) -> Result<()> {
+        let lines = self.state.lines.clone();
+        let deadlines = self.line_deadlines.clone();
+
+        let res = self.display_cached(&lines);
+        self.line_deadlines = deadlines;
+
+        res
+    }
+
+    /// Export the appearance of the XOSD window as TOML.
+    ///
+    /// The config contains the number of lines and every setting cached in
+    /// [`Xosd::snapshot`], but not the displayed data. Use
+    /// [`Xosd::from_config`] to create a XOSD window from it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(2)?;
+    ///
+    /// osd.set_color("LimeGreen")?;
+    /// osd.set_timeout(3)?;
+    ///
+    /// let config = osd.export_config();
+    ///
+    /// assert!(config.contains("color = \"LimeGreen\"\n"));
+    /// assert!(config.contains("timeout = 3\n"));
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn export_config(&self) -> String {
+        config::export(&self.state)
+    }
+
+    /// Create a new [`Xosd`] object from a config.
+    ///
+    /// `config` is TOML as written by [`Xosd::export_config`]. Settings
+    /// missing from the config keep the xosd default.
+    ///
+    /// # Errors
+    ///
+    /// * If `config` can't be parsed or has more than 1024 lines
+    ///   [`Error::InvalidConfig`] is returned.
+    /// * If the config has less than 1 line [`Error::InvalidLineCount`] is
+    ///   returned.
+    /// * If any of the underlying xosd functions fails the xosd error message
+    ///   is wrapped in a [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, VerticalAlign};
+    /// let mut osd = Xosd::from_config(
+    ///     r#"
+    ///     lines = 2
+    ///     color = "LimeGreen"
+    ///     vertical_align = "bottom"
+    ///     "#,
+    /// )?;
+    ///
+    /// assert_eq!(osd.max_lines()?, 2);
+    /// assert_eq!(osd.color()?, (50, 205, 50));
+    /// assert_eq!(osd.snapshot().vertical_align, VerticalAlign::Bottom);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn from_config(config: &str) -> Result<Self> {
+        let state = config::parse(config)?;
+        let mut osd = Self::new(state.lines.len().try_into()?)?;
+
+        osd.apply_state(&state)?;
+
+        Ok(osd)
+    }
+
+    /// Take a snapshot of the state of the XOSD window
+    ///
+    /// Returns everything that was set through this [`Xosd`] object, see
+    /// [`Snapshot`]. This is useful for debugging and bug reports.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, Command, VerticalAlign};
+    /// let mut osd = Xosd::new(2)?;
+    ///
+    /// osd.set_color("LimeGreen")?;
+    /// osd.set_vertical_align(VerticalAlign::Bottom)?;
+    /// osd.display(0, Command::string("Hello")?)?;
+    ///
+    /// let snapshot = osd.snapshot();
+    ///
+    /// assert_eq!(snapshot.color.as_deref(), Some("LimeGreen"));
+    /// assert_eq!(snapshot.vertical_align, VerticalAlign::Bottom);
+    /// assert_eq!(snapshot.lines, [Some(Command::string("Hello")?), None]);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            visible: call_xosd!(xosd_is_onscreen(self.handle)) == 1,
+            ..self.state.clone()
+        }
+    }
+
+    /// Let the XOSD window grow when more lines are displayed than it has.
+    ///
+    /// Once enabled, displaying data on a line past the last one or passing
+    /// more lines than the window has to a helper like [`Xosd::reveal_lines`]
+    /// recreates the XOSD window with enough lines instead of failing. The
+    /// window never grows past `limit` lines. Passing [`None`] disables
+    /// growing, which is the default.
+    ///
+    /// Growing is expensive: a new XOSD window is created, every setting and
+    /// every line is applied to it again and the old window is destroyed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(1)?;
+    /// osd.set_auto_grow(Some(10));
+    ///
+    /// osd.display(0, Command::string("First entry")?)?;
+    /// osd.display(1, Command::string("Second entry")?)?;
+    ///
+    /// assert_eq!(osd.max_lines()?, 2);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_auto_grow(&mut self, limit: Option<i32>) {
+        self.auto_grow = limit;
+    }
+
+    /// Change the maximum number of lines of the XOSD window.
+    ///
+    /// xosd can't resize a window, so like [`Xosd::set_auto_grow`] this
+    /// creates a new XOSD window, applies every setting and line to it again
+    /// and destroys the old window. Lines that don't fit into the new window
+    /// are dropped. If the number of lines does not change nothing is done.
+    ///
+    /// # Errors
+    ///
+    /// * If `lines` is less than 1 [`Error::InvalidLineCount`] is returned.
+    /// * If any of the underlying xosd functions fails the xosd error message
+    ///   is wrapped in a [`Error::XosdError`] and returned. The old window is
+    ///   kept in that case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::{Xosd, Command};
+    /// let mut osd = Xosd::new(1)?;
+    /// osd.display(0, Command::percentage(40)?)?;
+    ///
+    /// osd.set_lines(3)?;
+    /// osd.display(2, Command::string("Track 3")?)?;
+    ///
+    /// assert_eq!(osd.max_lines()?, 3);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn set_lines(&mut self, lines: i32) -> Result<()> {
+        if lines < 1 {
+            return Err(Error::InvalidLineCount);
+        }
+
+        if lines as usize == self.state.lines.len() {
+            return Ok(());
+        }
+
+        self.resize(lines)
+    }
+
+    /// Grow the window to `lines` if auto grow allows it.
+    fn grow(&mut self, lines: i32) -> Result<()> {
+        match self.auto_grow {
+            Some(limit) if lines <= limit && lines as usize > self.state.lines.len() => {
+                self.resize(lines)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Recreate the XOSD window with `lines` lines keeping all settings.
+    ///
+    /// Lines that don't fit into the new window are dropped. If applying the
+    /// settings fails the old window is kept.
+    fn resize(&mut self, lines: i32) -> Result<()> {
+        let handle = call_xosd!(xosd_create(lines));
+        if handle.is_null() {
+            return Err(last_error());
+        }
+
+        let old_handle = std::mem::replace(&mut self.handle, handle);
+        let old_state = self.state.clone();
+        let old_deadlines = self.line_deadlines.clone();
+        let burst_timeout = self.burst_timeout;
+        let count = lines.try_into().unwrap_or_default();
+
+        self.state = Snapshot::new(count);
+
+        match self.apply_state(&old_state) {
+            Ok(()) => {
+                self.line_deadlines = old_deadlines;
+                self.line_deadlines.resize(count, None);
+                self.burst_timeout = burst_timeout;
+                call_xosd!(xosd_uninit(old_handle));
+                Ok(())
+            }
+            Err(err) => {
+                call_xosd!(xosd_uninit(self.handle));
+                self.handle = old_handle;
+                self.state = old_state;
+                self.line_deadlines = old_deadlines;
+                self.burst_timeout = burst_timeout;
+                Err(err)
+            }
+        }
+    }
+
+    /// Change the text color without caching it or calling the callback set
+    /// with [`Xosd::set_on_color_change`].
+    ///
+    /// Returns the color passed to xosd.
+    fn set_raw_color<S>(&mut self, color: S) -> Result<CString>
+    where
+        S: Into<Vec<u8>>,
+    {
+        let color = checked_cstring(color, "color", Error::InvalidColor)?;
+        debug!("set color to {:?}", color);
+
+        wrap_unsafe!(xosd_set_colour(self.handle, color.as_ptr()))?;
+
+        Ok(color)
+    }
+
+    /// Switch back to the text color `previous` cached before it was changed
+    /// with [`Xosd::set_raw_color`].
+    ///
+    /// [`None`] is the xosd default color.
+    fn restore_color(&mut self, previous: Option<String>) -> Result<()> {
+        match &previous {
+            Some(color) => self.set_raw_color(color.as_str())?,
+            None => self.set_raw_color(&*default_color()?)?,
+        };
+        self.state.color = previous;
+
+        Ok(())
+    }
+
+    /// Apply every setting of `state` to the XOSD window.
+    ///
+    /// Unlike [`Xosd::apply_state`] settings that were never changed are set
+    /// to the xosd default and the lines are not displayed.
+    fn apply_settings(&mut self, state: &Snapshot) -> Result<()> {
+        match &state.font {
+            Some(font) => self.set_font(font.as_str())?,
+            None => self.set_font(&*default_font()?)?,
+        }
+        match &state.color {
+            Some(color) => self.set_color(color.as_str())?,
+            None => self.set_color(&*default_color()?)?,
+        }
+        self.set_shadow_color(state.shadow_color.as_deref().unwrap_or("black"))?;
+        self.set_outline_color(state.outline_color.as_deref().unwrap_or("black"))?;
+
+        self.set_raw_timeout(state.timeout)?;
+        self.set_bar_length(state.bar_length)?;
+        self.set_vertical_align(state.vertical_align)?;
+        self.set_horizontal_align(state.horizontal_align)?;
+        self.set_vertical_offset(state.vertical_offset)?;
+        self.set_horizontal_offset(state.horizontal_offset)?;
+        self.set_shadow_offset(state.shadow_offset)?;
+        self.set_outline_offset(state.outline_offset)?;
+
+        self.state.font = state.font.clone();
+        self.state.color = state.color.clone();
+        self.state.shadow_color = state.shadow_color.clone();
+        self.state.outline_color = state.outline_color.clone();
+
+        Ok(())
+    }
+
+    /// Apply every cached setting and line of `state` to the XOSD window.
+    fn apply_state(&mut self, state: &Snapshot) -> Result<()> {
+        if let Some(font) = &state.font {
+            self.set_font(font.as_str())?;
+        }
+        if let Some(color) = &state.color {
+            self.set_color(color.as_str())?;
+        }
+        if let Some(color) = &state.shadow_color {
+            self.set_shadow_color(color.as_str())?;
+        }
+        if let Some(color) = &state.outline_color {
+            self.set_outline_color(color.as_str())?;
+        }
+
+        self.set_raw_timeout(state.timeout)?;
+        self.set_bar_length(state.bar_length)?;
+        self.set_vertical_align(state.vertical_align)?;
+        self.set_horizontal_align(state.horizontal_align)?;
+        self.set_vertical_offset(state.vertical_offset)?;
+        self.set_horizontal_offset(state.horizontal_offset)?;
+        self.set_shadow_offset(state.shadow_offset)?;
+        self.set_outline_offset(state.outline_offset)?;
+
+        self.display_cached(&state.lines)
+    }
+
+    /// Display `lines` as they were cached, skipping lines that don't fit
+    fn display_cached(&mut self, lines: &[Option<Command>]) -> Result<()> {
+        let max = self.state.lines.len();
+        for (line, command) in (0..).zip(lines).take(max) {
+            if let Some(command) = command {
+                self.display_raw(line, command.clone())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_line(&mut self, line: i32) -> Result<()> {
+        let max = self.max_lines()?;
+
+        if line < 0 || line >= max {
+            Err(Error::InvalidLine { line, max })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Translate a negative `line` counted from the last line into the line
+    /// counted from the first one. Other lines are returned unchanged.
+    fn absolute_line(&self, line: i32) -> Result<i32> {
+        if line >= 0 {
+            return Ok(line);
+        }
+
+        let max: i32 = self.state.lines.len().try_into()?;
+        match line.checked_add(max) {
+            Some(index) if index >= 0 => Ok(index),
+            _ => Err(Error::InvalidLine { line, max }),
+        }
+    }
+
+    /// Like [`Xosd::check_line`] but uses the number of cached lines instead
+    /// of asking xosd
+    fn check_cached_line(&self, line: i32) -> Result<()> {
+        let max = self.state.lines.len();
+
+        match usize::try_from(line) {
+            Ok(index) if index < max => Ok(()),
+            _ => Err(Error::InvalidLine {
+                line,
+                max: max.try_into()?,
+            }),
+        }
+    }
+
+    fn check_line_count(&mut self, lines: usize) -> Result<i32> {
+        if let Ok(lines) = lines.try_into() {
+            self.grow(lines)?;
+        }
+
+        let max = self.max_lines()?;
+
+        match lines.try_into() {
+            Ok(count) if count <= max => Ok(count),
+            _ => Err(Error::TooManyLines { lines, max }),
+        }
+    }
+
+    /// Get the maximum number of lines that can be displayed on the XOSD window.
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_get_number_lines` fails the xosd error message is wrapped in a
+    /// [`Error::XosdError`] and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// assert_eq!(Xosd::new(1)?.max_lines()?, 1);
+    /// assert_eq!(Xosd::new(123)?.max_lines()?, 123);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn max_lines(&mut self) -> Result<i32> {
+        let res = call_xosd!(xosd_get_number_lines(self.handle));
+
+        if res < 0 {
+            Err(last_error())
+        } else {
+            Ok(res.into())
+        }
+    }
+
+    /// Get the number of lines that currently hold content.
+    ///
+    /// xosd can't report what a line shows, so this counts the lines in the
+    /// cache kept for [`Xosd::snapshot`]. A line holds content once anything
+    /// but an empty string was displayed on it. Lines are emptied by
+    /// displaying an empty string, by [`Xosd::scroll`] and by
+    /// [`Xosd::expire_lines`].
+    ///
+    /// Hiding the window, either with [`Xosd::hide`] or by the timeout, does
+    /// not empty the lines. xosd shows them again on the next
+    /// [`Xosd::show`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(3)?;
+    ///
+    /// osd.display_string(0, "Hello,")?;
+    /// osd.display_string(1, "World!")?;
+    /// assert_eq!(osd.displayed_lines(), 2);
+    ///
+    /// osd.scroll(1)?;
+    /// assert_eq!(osd.displayed_lines(), 1);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn displayed_lines(&self) -> usize {
+        self.state
+            .lines
+            .iter()
+            .flatten()
+            .filter(|command| !matches!(command, Command::String(string) if string.is_empty()))
+            .count()
+    }
+
+    /// Get the value of the percentage bar or slider shown on a line.
+    ///
+    /// xosd can't report what a line shows, so this reads the cache kept for
+    /// [`Xosd::snapshot`]. It returns the value last displayed on `line` with
+    /// [`Command::Percentage`] or [`Command::Slider`]. The value is kept while
+    /// the window is hidden and moves with the line on [`Xosd::scroll`].
+    ///
+    /// A negative `line` counts from the last line. Returns [`None`] if `line`
+    /// is out of range, nothing was displayed on it or it shows text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.display_slider(0, 40)?;
+    ///
+    /// // Turn up the volume
+    /// let volume = osd.current_value(0).unwrap_or(1);
+    /// osd.display_slider(0, (volume + 5).min(100))?;
+    ///
+    /// assert_eq!(osd.current_value(0), Some(45));
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn current_value(&self, line: i32) -> Option<u16> {
+        let line = usize::try_from(self.absolute_line(line).ok()?).ok()?;
+
+        match self.state.lines.get(line)? {
+            Some(Command::Percentage(value)) | Some(Command::Slider(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get the text shown on a line.
+    ///
+    /// Like [`Xosd::current_value`] this reads the cache kept for
+    /// [`Xosd::snapshot`], so it reflects what this [`Xosd`] object displayed
+    /// and not necessarily what the X server shows. The text is cached after
+    /// the text transform was applied. It is updated by [`Xosd::display`],
+    /// [`Xosd::clear_line`] and [`Xosd::scroll`].
+    ///
+    /// A negative `line` counts from the last line. Returns [`None`] if `line`
+    /// is out of range, nothing was displayed on it, it was cleared or it
+    /// shows a percentage bar or slider.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.display_string(0, "Now playing")?;
+    /// assert_eq!(osd.line_content(0), Some("Now playing"));
+    ///
+    /// // Skip the redundant update
+    /// if osd.line_content(0) != Some("Now playing") {
+    ///     osd.display_string(0, "Now playing")?;
+    /// }
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn line_content(&self, line: i32) -> Option<&str> {
+        let line = usize::try_from(self.absolute_line(line).ok()?).ok()?;
+
+        match self.state.lines.get(line)? {
+            Some(Command::String(text)) if !text.is_empty() => Some(text),
+            _ => None,
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    #[ignore = "success depends on test order"]
+    fn test_error() {
+        assert_eq!(error_str().unwrap(), Cow::from(""))
+    }
+
+    #[test]
+    fn test_new() {
+        drop(Xosd::new(12).unwrap())
+    }
+
+    #[test]
+    fn test_send() {
+        let mut osd = Xosd::new(1).unwrap();
+        osd.set_color("LimeGreen").unwrap();
+
+        let mut osd = thread::spawn(move || {
+            osd.display(0, Command::string("From another thread").unwrap())
+                .unwrap();
+            osd
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(osd.color(), Ok((50, 205, 50)));
+    }
+
+    #[test]
+    fn test_new_zero_line() {
+        assert_eq!(Xosd::new(0).err(), Some(Error::InvalidLineCount))
+    }
+
+    #[test]
+    fn test_new_negative_lines() {
+        assert_eq!(Xosd::new(-1).err(), Some(Error::InvalidLineCount));
+        assert_eq!(Xosd::new(i32::MIN).err(), Some(Error::InvalidLineCount));
+    }
+
+    #[test]
+    fn test_new_nonzero() {
+        let mut osd = Xosd::new_nonzero(NonZeroU32::new(3).unwrap()).unwrap();
+
+        assert_eq!(osd.max_lines(), Ok(3));
+    }
+
+    #[test]
+    fn test_new_nonzero_overflow() {
+        assert!(matches!(
+            Xosd::new_nonzero(NonZeroU32::new(u32::MAX).unwrap()),
+            Err(Error::TryFromIntError(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_latin1() {
+        assert_eq!(
+            Encoding::Latin1.decode(&[0x47, 0x72, 0xfc, 0xdf, 0x65, 0xa9, 0xff]),
+            "Grüße©ÿ"
+        );
+    }
+
+    #[test]
+    fn test_decode_ascii() {
+        assert_eq!(Encoding::Ascii.decode(b"na\xefve"), "na\u{fffd}ve");
+    }
+
+    #[test]
+    fn test_decode_utf8() {
+        assert_eq!(Encoding::Utf8.decode("Grüße".as_bytes()), "Grüße");
+        assert_eq!(Encoding::Utf8.decode(b"Gr\xfc\xdfe"), "Gr\u{fffd}\u{fffd}e");
+    }
+
+    #[test]
+    fn test_set_color_control_character() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        assert_eq!(
+            osd.set_color("Lime\x1bGreen"),
+            Err(Error::InvalidColor("Lime\x1bGreen".to_string()))
+        );
+        assert_eq!(
+            osd.set_shadow_color("\x1b[0mWhite"),
+            Err(Error::InvalidColor("\x1b[0mWhite".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_set_font_control_character() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        assert_eq!(
+            osd.set_font("fixed\x1b"),
+            Err(Error::InvalidFont("fixed\x1b".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_checked_cstring_nul() {
+        assert!(matches!(
+            checked_cstring("fi\0xed", "font", Error::InvalidFont),
+            Err(Error::InvalidString { field: "font", .. })
+        ));
+    }
+
+    #[test]
+    fn test_set_font_nul() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        let error = osd.set_font("fi\0xed").unwrap_err();
+        assert!(matches!(
+            &error,
+            Error::InvalidString { field: "font", source } if source.nul_position() == 2
+        ));
+        assert_eq!(error.to_string(), "Invalid font: NUL byte at position 2");
+        assert_eq!(osd.snapshot().font, None);
+    }
+
+    #[test]
+    fn test_display_nul() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        let error = osd.display_string(0, "Now\0playing").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid string: NUL byte at position 3");
+        assert_eq!(osd.snapshot().lines, [None]);
+    }
+
+    #[test]
+    fn test_text_transform() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        assert_eq!(osd.transform_text("Hello".to_string()), "Hello");
+
+        osd.set_text_transform(|text| text.to_uppercase());
+        assert_eq!(osd.transform_text("Hello".to_string()), "HELLO");
+        assert_eq!(osd.display(0, Command::string("Hello").unwrap()), Ok(5));
+
+        osd.clear_text_transform();
+        assert_eq!(osd.transform_text("Hello".to_string()), "Hello");
+    }
+
+    #[test]
+    fn test_table_row() {
+        assert_eq!(table_row("CPU", "12%", 10), "CPU    12%");
+        assert_eq!(table_row("NET", "1.2 MB/s", 14), "NET   1.2 MB/s");
+        assert_eq!(table_row("Größe", "5", 8), "Größe  5");
+        assert_eq!(table_row("Temperature", "45°C", 10), "Temperature 45°C");
+        assert_eq!(table_row("", "", 3), "   ");
+    }
+
+    #[test]
+    fn test_wrap_words() {
+        assert_eq!(
+            wrap_words("The quick brown fox jumps", 10, 5),
+            ["The quick", "brown fox", "jumps"]
+        );
+        assert_eq!(wrap_words("  many   spaces ", 20, 5), ["many spaces"]);
+        assert_eq!(wrap_words("abcdefghij", 4, 5), ["abcd", "efgh", "ij"]);
+        assert_eq!(wrap_words("Größe über", 5, 5), ["Größe", "über"]);
+        assert_eq!(wrap_words("", 10, 5), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_wrap_words_truncated() {
+        assert_eq!(
+            wrap_words("The quick brown fox jumps over the lazy dog", 16, 2),
+            ["The quick brown", "fox jumps ove..."]
+        );
+        assert_eq!(wrap_words("one two three", 3, 1), ["..."]);
+        assert_eq!(wrap_words("one two three", 2, 1), [".."]);
+        assert_eq!(wrap_words("a b c", 1, 2), ["a", "."]);
+    }
+
+    #[test]
+    fn test_display_wrapped() {
+        let mut osd = Xosd::new(3).unwrap();
+        osd.display_string(0, "Title").unwrap();
+
+        osd.display_wrapped(1, "one two three four", 9).unwrap();
+
+        let text = |text: &str| Some(Command::String(text.to_string()));
+        assert_eq!(
+            osd.snapshot().lines,
+            [text("Title"), text("one two"), text("three...")]
+        );
+
+        assert_eq!(
+            osd.display_wrapped(3, "text", 9),
+            Err(Error::InvalidLine { line: 3, max: 3 })
+        );
+
+        osd.display_wrapped(-2, "five six seven", 9).unwrap();
+        assert_eq!(
+            osd.snapshot().lines,
+            [text("Title"), text("five six"), text("seven")]
+        );
+        assert_eq!(
+            osd.display_wrapped(-4, "text", 9),
+            Err(Error::InvalidLine { line: -4, max: 3 })
+        );
+    }
+
+    #[test]
+    fn test_display_truncated() {
+        let mut osd = Xosd::new(1).unwrap();
+        let mut truncated = |text: &str, max_chars| {
+            osd.display_truncated(0, text, max_chars).unwrap();
+            match &osd.snapshot().lines[0] {
+                Some(Command::String(text)) => text.clone(),
+                line => panic!("unexpected line {:?}", line),
+            }
+        };
+
+        assert_eq!(truncated("abcdef", 6), "abcdef");
+        assert_eq!(truncated("abcdefg", 6), "abc...");
+        assert_eq!(truncated("", 0), "");
+        assert_eq!(truncated("ab", 1), ".");
+        assert_eq!(truncated("Grüße", 5), "Grüße");
+        assert_eq!(truncated("Grüße!", 5), "Gr...");
+        assert_eq!(truncated("über äöü", 7), "über...");
+    }
+
+    #[test]
+    fn test_display_truncated_invalid_line() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        assert_eq!(
+            osd.display_truncated(1, "text", 10),
+            Err(Error::InvalidLine { line: 1, max: 1 })
+        );
+    }
+
+    #[test]
+    fn test_display_table() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        assert_eq!(
+            osd.display_table(&[("CPU", "12%"), ("MEM", "48%")], 10),
+            Ok(())
+        );
+        assert_eq!(
+            osd.snapshot().lines,
+            [
+                Some(Command::String("CPU    12%".to_string())),
+                Some(Command::String("MEM    48%".to_string())),
+            ]
+        );
+        assert_eq!(
+            osd.display_table(&[("CPU", "12%"), ("MEM", "48%"), ("NET", "0%")], 10),
+            Err(Error::TooManyLines { lines: 3, max: 2 })
+        );
+    }
+
+    #[test]
+    fn test_display_with_fade() {
+        let mut osd = Xosd::new(1).unwrap();
+        let colors = Arc::new(Mutex::new(Vec::new()));
+
+        osd.set_color("LimeGreen").unwrap();
+
+        let received = Arc::clone(&colors);
+        osd.set_on_color_change(move |color| received.lock().unwrap().push(color));
+
+        osd.display_with_fade(
+            0,
+            Command::string("Fading").unwrap(),
+            Duration::from_millis(1),
+            Duration::from_millis(200),
+        )
+        .unwrap();
+
+        assert_eq!(osd.onscreen(), Ok(false));
+        assert_eq!(osd.snapshot().color.as_deref(), Some("LimeGreen"));
+        assert_eq!(osd.color(), Ok((50, 205, 50)));
+        assert!(colors.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_on_color_change() {
+        let mut osd = Xosd::new(1).unwrap();
+        let colors = Arc::new(Mutex::new(Vec::new()));
+
+        osd.set_color("White").unwrap();
+
+        let received = Arc::clone(&colors);
+        osd.set_on_color_change(move |color| received.lock().unwrap().push(color));
+
+        osd.set_color("LimeGreen").unwrap();
+        assert!(osd.set_color("\x07").is_err());
+        osd.set_color("#102030").unwrap();
+
+        osd.clear_on_color_change();
+        osd.set_color("White").unwrap();
+
+        assert_eq!(
+            *colors.lock().unwrap(),
+            [Color::rgb(50, 205, 50), Color::rgb(0x10, 0x20, 0x30)]
+        );
+    }
+
+    #[test]
+    fn test_burst() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.set_timeout(3).unwrap();
+        osd.begin_burst().unwrap();
+        osd.begin_burst().unwrap();
+        assert_eq!(osd.snapshot().timeout, -1);
+
+        osd.display(0, Command::percentage(10).unwrap()).unwrap();
+        osd.end_burst().unwrap();
+        assert_eq!(osd.snapshot().timeout, 3);
+
+        osd.set_timeout(5).unwrap();
+        osd.end_burst().unwrap();
+        assert_eq!(osd.snapshot().timeout, 5);
+    }
+
+    #[test]
+    fn test_burst_set_timeout() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.set_timeout(3).unwrap();
+        osd.begin_burst().unwrap();
+        osd.set_timeout(5).unwrap();
+        osd.end_burst().unwrap();
+
+        assert_eq!(osd.snapshot().timeout, 5);
+    }
+
+    #[test]
+    fn test_timeout_seconds() {
+        assert_eq!(timeout_seconds(Duration::from_secs(0)), Ok(0));
+        assert_eq!(timeout_seconds(Duration::from_nanos(1)), Ok(1));
+        assert_eq!(timeout_seconds(Duration::from_millis(999)), Ok(1));
+        assert_eq!(timeout_seconds(Duration::from_secs(3)), Ok(3));
+        assert_eq!(timeout_seconds(Duration::from_millis(3001)), Ok(4));
+        assert_eq!(
+            timeout_seconds(Duration::from_secs(i32::MAX as u64)),
+            Ok(i32::MAX)
+        );
+        assert!(matches!(
+            timeout_seconds(Duration::from_secs(i32::MAX as u64 + 1)),
+            Err(Error::TryFromIntError(_))
+        ));
+    }
+
+    #[test]
+    fn test_set_timeout_duration() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.set_timeout_duration(Duration::from_millis(200))
+            .unwrap();
+        assert_eq!(osd.snapshot().timeout, 1);
+
+        assert!(osd
+            .set_timeout_duration(Duration::from_secs(u64::MAX))
+            .is_err());
+        assert_eq!(osd.snapshot().timeout, 1);
+    }
+
+    #[test]
+    fn test_prefixes() {
+        assert_eq!(prefixes("Hey").collect::<Vec<_>>(), ["H", "He", "Hey"]);
+        assert_eq!(prefixes("äö").collect::<Vec<_>>(), ["ä", "äö"]);
+        assert_eq!(prefixes("").collect::<Vec<_>>(), [""]);
+    }
+
+    #[test]
+    fn test_type_out() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        assert_eq!(osd.type_out(1, "Hey", Duration::from_millis(1)), Ok(()));
+        assert_eq!(
+            osd.type_out(2, "Hey", Duration::from_millis(1)),
+            Err(Error::InvalidLine { line: 2, max: 2 })
+        );
+        assert_eq!(osd.type_out(-2, "Ho", Duration::from_millis(1)), Ok(()));
+        assert_eq!(osd.line_content(0), Some("Ho"));
+        assert_eq!(
+            osd.type_out(-3, "Hey", Duration::from_millis(1)),
+            Err(Error::InvalidLine { line: -3, max: 2 })
+        );
+    }
+
+    #[test]
+    fn test_type_out_prefixes() {
+        let mut osd = Xosd::new(1).unwrap();
+        let displayed = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&displayed);
+        osd.set_text_transform(move |text| {
+            recorded.lock().unwrap().push(text.to_string());
+            text.to_string()
+        });
+
+        osd.type_out(0, "Hello", Duration::from_millis(1)).unwrap();
+        assert_eq!(
+            *displayed.lock().unwrap(),
+            ["H", "He", "Hel", "Hell", "Hello"]
+        );
+        assert_eq!(osd.line_content(0), Some("Hello"));
+    }
+
+    #[test]
+    fn test_reveal_lines() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        assert_eq!(
+            osd.reveal_lines(&["One", "Two"], Duration::from_millis(1)),
+            Ok(())
+        );
+        assert_eq!(osd.reveal_lines(&[], Duration::from_millis(1)), Ok(()));
+        assert_eq!(
+            osd.reveal_lines(&["One", "Two", "Three"], Duration::from_millis(1)),
+            Err(Error::TooManyLines { lines: 3, max: 2 })
+        );
+    }
+
+    #[test]
+    fn test_reveal_lines_order() {
+        let mut osd = Xosd::new(3).unwrap();
+        let displayed = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&displayed);
+        osd.set_text_transform(move |text| {
+            recorded.lock().unwrap().push(text.to_string());
+            text.to_string()
+        });
+
+        osd.reveal_lines(&["One", "Two"], Duration::from_millis(1))
+            .unwrap();
+        assert_eq!(*displayed.lock().unwrap(), ["One", "Two"]);
+        assert_eq!(
+            osd.snapshot().lines,
+            [
+                Some(Command::String("One".to_string())),
+                Some(Command::String("Two".to_string())),
+                None,
+            ]
+        );
+
+        #[cfg(feature = "mock")]
+        assert_eq!(
+            osd.mock_operations()
+                .iter()
+                .filter(|&&operation| operation == "xosd_display")
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_countdown() {
+        let mut osd = Xosd::new(2).unwrap();
+        let displayed = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&displayed);
+        osd.set_text_transform(move |text| {
+            recorded.lock().unwrap().push(text.to_string());
+            text.to_string()
+        });
+
+        let format = |value| format!("Hiding in {}", value);
+        assert_eq!(
+            osd.countdown(1, 2, Duration::from_millis(1), format),
+            Ok(())
+        );
+        assert_eq!(
+            *displayed.lock().unwrap(),
+            ["Hiding in 2", "Hiding in 1", "Hiding in 0"]
+        );
+        assert_eq!(
+            osd.countdown(2, 2, Duration::from_millis(1), format),
+            Err(Error::InvalidLine { line: 2, max: 2 })
+        );
+
+        assert_eq!(
+            osd.countdown(-2, 0, Duration::from_millis(1), format),
+            Ok(())
+        );
+        assert_eq!(osd.line_content(0), Some("Hiding in 0"));
+    }
+
+    #[test]
+    fn test_auto_grow() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        osd.set_color("LimeGreen").unwrap();
+        osd.set_timeout(3).unwrap();
+        osd.set_vertical_align(VerticalAlign::Bottom).unwrap();
+        osd.display(0, Command::string("One").unwrap()).unwrap();
+        osd.display(1, Command::string("Two").unwrap()).unwrap();
+        assert!(osd.display(2, Command::string("Three").unwrap()).is_err());
+
+        osd.set_auto_grow(Some(4));
+        osd.display(2, Command::string("Three").unwrap()).unwrap();
+
+        assert_eq!(osd.max_lines(), Ok(3));
+        assert_eq!(osd.color(), Ok((50, 205, 50)));
+
+        let snapshot = osd.snapshot();
+        assert_eq!(snapshot.timeout, 3);
+        assert_eq!(snapshot.vertical_align, VerticalAlign::Bottom);
+        assert_eq!(
+            snapshot.lines,
+            [
+                Some(Command::String("One".to_string())),
+                Some(Command::String("Two".to_string())),
+                Some(Command::String("Three".to_string())),
+            ]
+        );
+
+        assert_eq!(
+            osd.reveal_lines(&["1", "2", "3", "4"], Duration::from_millis(1)),
+            Ok(())
+        );
+        assert_eq!(osd.max_lines(), Ok(4));
+        assert_eq!(
+            osd.reveal_lines(&["1", "2", "3", "4", "5"], Duration::from_millis(1)),
+            Err(Error::TooManyLines { lines: 5, max: 4 })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_config_round_trip() {
+        let mut osd = Xosd::new(3).unwrap();
+
+        osd.set_color("LimeGreen").unwrap();
+        osd.set_font("fixed").unwrap();
+        osd.set_shadow_color("Black").unwrap();
+        osd.set_timeout(5).unwrap();
+        osd.set_vertical_align(VerticalAlign::Bottom).unwrap();
+        osd.set_horizontal_align(HorizontalAlign::Right).unwrap();
+        osd.set_vertical_offset(48).unwrap();
+        osd.set_horizontal_offset(-10).unwrap();
+        osd.set_shadow_offset(2).unwrap();
+        osd.set_outline_offset(1).unwrap();
+        osd.display(0, Command::string("Not exported").unwrap())
+            .unwrap();
+
+        let mut restored = Xosd::from_config(&osd.export_config()).unwrap();
+
+        assert_eq!(restored.max_lines(), Ok(3));
+        assert_eq!(restored.color(), osd.color());
+        assert_eq!(
+            restored.snapshot(),
+            Snapshot {
+                lines: vec![None; 3],
+                visible: restored.onscreen().unwrap(),
+                ..osd.snapshot()
+            }
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_from_config_invalid() {
+        assert_eq!(
+            Xosd::from_config("lines = 0").unwrap_err(),
+            Error::InvalidLineCount
+        );
+        assert!(matches!(
+            Xosd::from_config("lines = \"two\""),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            Xosd::from_config("lines = 2000000000"),
+            Err(Error::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_broadcast() {
+        let mut osd = Xosd::new(3).unwrap();
+
+        assert_eq!(
+            osd.broadcast(Command::string("Hey").unwrap()),
+            Ok(vec![3, 3, 3])
+        );
+    }
+
+    #[test]
+    fn test_default_align() {
+        assert_eq!(VerticalAlign::default(), VerticalAlign::Top);
+        assert_eq!(HorizontalAlign::default(), HorizontalAlign::Left);
+    }
+
+    #[test]
+    fn test_display_result() {
+        assert_eq!(display_result(0), Ok(0));
+        assert_eq!(display_result(42), Ok(42));
+        assert_eq!(display_result(u16::MAX.into()), Ok(u16::MAX));
+    }
+
+    #[test]
+    fn test_display_result_overflow() {
+        assert!(matches!(
+            display_result(c_int::from(u16::MAX) + 1),
+            Err(Error::TryFromIntError(_))
+        ));
+    }
+
+    // The mock returns the length of the string like xosd does
+    #[cfg(feature = "mock")]
+    #[test]
+    fn test_display_overflow() {
+        let mut osd = Xosd::new(1).unwrap();
+        let text = "a".repeat(usize::from(u16::MAX) + 1);
+
+        assert!(matches!(
+            osd.display(0, Command::String(text)),
+            Err(Error::TryFromIntError(_))
+        ));
+        assert_eq!(
+            osd.display(0, Command::String("a".repeat(usize::from(u16::MAX)))),
+            Ok(u16::MAX)
+        );
+    }
+
+    #[test]
+    fn test_error_operation() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        match osd.set_font("-bogus-font-") {
+            Err(Error::XosdError(message)) => assert!(
+                message.starts_with("[during set_font] "),
+                "unexpected message: {}",
+                message
+            ),
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        match osd.clear_line(3) {
+            Err(Error::XosdError(message)) => assert!(
+                message.starts_with("[during display] "),
+                "unexpected message: {}",
+                message
+            ),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "emoji")]
+    fn test_demoji() {
+        assert_eq!(demoji("Launching 🚀!"), "Launching [rocket]!");
+        assert_eq!(demoji("🚀🚀"), "[rocket][rocket]");
+        assert_eq!(demoji("Grüße"), "Grüße");
+    }
+
+    #[test]
+    #[cfg(feature = "emoji")]
+    fn test_string_demoji() {
+        assert_eq!(
+            Command::string_demoji("Volume 🚀"),
+            Ok(Command::String("Volume [rocket]".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_block_center_offset() {
+        assert_eq!(block_center_offset(5, 5, 20), 0);
+        assert_eq!(block_center_offset(1, 5, 20), -40);
+        assert_eq!(block_center_offset(2, 5, 20), -30);
+        assert_eq!(block_center_offset(2, 3, 15), -7);
+    }
+
+    #[test]
+    fn test_display_block_centered() {
+        let mut osd = Xosd::new(3).unwrap();
+
+        assert_eq!(
+            osd.display_block_centered(&["Hello,", "World!"], 20),
+            Ok(())
+        );
+        assert_eq!(
+            osd.display_block_centered(&["1", "2", "3", "4"], 20),
+            Err(Error::TooManyLines { lines: 4, max: 3 })
+        );
+    }
+
+    #[test]
+    fn test_color16() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.set_color("White").unwrap();
+        assert_eq!(osd.color16(), Ok((65535, 65535, 65535)));
+
+        osd.set_color("#102030405060").unwrap();
+        assert_eq!(osd.color16(), Ok((0x1020, 0x3040, 0x5060)));
+    }
+
+    #[test]
+    fn test_set_color_rgb16() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.set_color_rgb16(0x1234, 0xabcd, 0x0f0f).unwrap();
+        assert_eq!(osd.color16(), Ok((0x1234, 0xabcd, 0x0f0f)));
+        assert_eq!(osd.snapshot().color.as_deref(), Some("#1234abcd0f0f"));
+
+        osd.set_color_rgb16(0, 0xffff, 1).unwrap();
+        assert_eq!(osd.color16(), Ok((0, 0xffff, 1)));
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut osd = Xosd::new(3).unwrap();
+
+        osd.set_text_transform(|text| text.to_uppercase());
+        osd.set_color("LimeGreen").unwrap();
+        osd.set_font("fixed").unwrap();
+        osd.set_shadow_color("Black").unwrap();
+        osd.set_timeout(5).unwrap();
+        osd.set_bar_length(Some(40)).unwrap();
+        osd.set_horizontal_align(HorizontalAlign::Center).unwrap();
+        osd.set_vertical_offset(48).unwrap();
+        osd.set_shadow_offset(2).unwrap();
+        osd.display(0, Command::string("Hello").unwrap()).unwrap();
+        osd.display(1, Command::percentage(40).unwrap()).unwrap();
+        osd.display(2, Command::string("World").unwrap()).unwrap();
+        osd.scroll(1).unwrap();
+
+        assert_eq!(
+            osd.snapshot(),
+            Snapshot {
+                lines: vec![
+                    Some(Command::Percentage(40)),
+                    Some(Command::String("WORLD".to_string())),
+                    None,
+                ],
+                color: Some("LimeGreen".to_string()),
+                font: Some("fixed".to_string()),
+                shadow_color: Some("Black".to_string()),
+                outline_color: None,
+                timeout: 5,
+                bar_length: Some(40),
+                vertical_align: VerticalAlign::Top,
+                horizontal_align: HorizontalAlign::Center,
+                vertical_offset: 48,
+                horizontal_offset: 0,
+                shadow_offset: 2,
+                outline_offset: 0,
+                visible: osd.onscreen().unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_snapshot_failed_setter() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.set_color("LimeGreen").unwrap();
+        assert!(osd.set_color("\x07").is_err());
+
+        assert_eq!(osd.snapshot().color.as_deref(), Some("LimeGreen"));
+    }
+
+    #[test]
+    fn test_display_line_for() {
+        let mut osd = Xosd::new(3).unwrap();
+        let start = Instant::now();
+
+        osd.display(0, Command::string("Status").unwrap()).unwrap();
+        osd.display_line_for(1, Command::string("Toast").unwrap(), Duration::from_secs(1))
+            .unwrap();
+        osd.display_line_for(2, Command::string("Later").unwrap(), Duration::from_secs(5))
+            .unwrap();
+
+        let deadline = osd.line_deadline().unwrap();
+        assert!(deadline >= start + Duration::from_secs(1));
+        assert!(deadline < start + Duration::from_secs(5));
+
+        osd.expire_lines_at(deadline).unwrap();
+
+        assert_eq!(
+            osd.snapshot().lines,
+            [
+                Some(Command::String("Status".to_string())),
+                Some(Command::String(String::new())),
+                Some(Command::String("Later".to_string())),
+            ]
+        );
+        assert!(osd.line_deadline().unwrap() >= start + Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_display_line_for_negative_line() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        osd.display_line_for(
+            -1,
+            Command::string("Toast").unwrap(),
+            Duration::from_secs(1),
+        )
+        .unwrap();
+        assert!(osd.line_deadline().is_some());
+
+        osd.expire_lines_at(Instant::now() + Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(
+            osd.snapshot().lines,
+            [None, Some(Command::String(String::new()))]
+        );
+
+        assert_eq!(
+            osd.display_line_for(-3, Command::Slider(1), Duration::from_secs(1)),
+            Err(Error::InvalidLine { line: -3, max: 2 })
+        );
+    }
+
+    #[test]
+    fn test_display_line_for_cancelled() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.display_line_for(0, Command::string("Toast").unwrap(), Duration::from_secs(1))
+            .unwrap();
+        osd.display(0, Command::string("Status").unwrap()).unwrap();
+
+        assert_eq!(osd.line_deadline(), None);
+    }
+
+    #[test]
+    fn test_outline_and_shadow_color() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.set_outline_color("Red").unwrap();
+        osd.set_shadow_color("White").unwrap();
+
+        let snapshot = osd.snapshot();
+        assert_eq!(snapshot.outline_color.as_deref(), Some("Red"));
+        assert_eq!(snapshot.shadow_color.as_deref(), Some("White"));
+        assert_eq!(osd.outline_color(), Ok((255, 0, 0)));
+        assert_eq!(osd.shadow_color(), Ok((255, 255, 255)));
+    }
+
+    #[test]
+    fn test_shadow_color() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        assert_eq!(osd.shadow_color(), Ok((0, 0, 0)));
+
+        osd.set_shadow_color("White").unwrap();
+        let white = Color::from(osd.shadow_color().unwrap());
+        assert!(white.approx_eq(&Color::rgb(255, 255, 255), 1));
+
+        assert!(osd.set_shadow_color("Gr\x07y").is_err());
+        assert_eq!(osd.shadow_color(), Ok((255, 255, 255)));
+    }
+
+    #[test]
+    fn test_outline_color() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        assert_eq!(osd.outline_color(), Ok((0, 0, 0)));
+
+        osd.set_outline_color("#102030").unwrap();
+        assert_eq!(osd.outline_color(), Ok((0x10, 0x20, 0x30)));
+    }
+
+    #[test]
+    fn test_cached_color_unknown() {
+        assert_eq!(
+            cached_color(Some("rgb:ff/00/00")),
+            Err(Error::InvalidColor("rgb:ff/00/00".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_display_prompt() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        assert_eq!(osd.display_prompt("Suspend now?", &["Yes", "No"]), Ok(()));
+        assert_eq!(osd.display_prompt("Continue?", &[]), Ok(()));
+        assert_eq!(osd.snapshot().timeout, -1);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn test_display_prompt_lines() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        osd.display_prompt("Suspend now?", &["Yes", "No"]).unwrap();
+        assert_eq!(osd.mock_line(0), Some("Suspend now?"));
+        assert_eq!(osd.mock_line(1), Some("Yes / No"));
+
+        osd.display_prompt("Continue?", &[]).unwrap();
+        assert_eq!(osd.mock_line(0), Some("Continue?"));
+        assert_eq!(osd.mock_line(1), Some(""));
+    }
+
+    #[test]
+    fn test_display_prompt_single_line() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        assert_eq!(
+            osd.display_prompt("Suspend now?", &["Yes", "No"]),
+            Err(Error::InvalidLine { line: 1, max: 1 })
+        );
+    }
+
+    #[test]
+    fn test_display_percentage_labeled() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        assert_eq!(
+            osd.display_percentage_labeled(0, LabelPosition::Below),
+            Ok(())
+        );
+        assert_eq!(
+            osd.display_percentage_labeled(47, LabelPosition::Below),
+            Ok(())
+        );
+        assert_eq!(
+            osd.display_percentage_labeled(100, LabelPosition::Above),
+            Ok(())
+        );
+        assert_eq!(
+            osd.display_percentage_labeled(101, LabelPosition::Below),
+            Err(Error::OutOfRangePercentage)
+        );
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn test_display_percentage_labeled_lines() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        osd.display_percentage_labeled(47, LabelPosition::Below)
+            .unwrap();
+        assert_eq!(osd.snapshot().lines[0], Some(Command::Percentage(47)));
+        assert_eq!(osd.mock_line(1), Some("47%"));
+
+        osd.display_percentage_labeled(12, LabelPosition::Above)
+            .unwrap();
+        assert_eq!(osd.mock_line(0), Some("12%"));
+        assert_eq!(osd.snapshot().lines[1], Some(Command::Percentage(12)));
+    }
+
+    #[test]
+    fn test_display_percentage_labeled_single_line() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        assert_eq!(
+            osd.display_percentage_labeled(47, LabelPosition::Below),
+            Err(Error::InvalidLine { line: 1, max: 1 })
+        );
+    }
+
+    #[test]
+    fn test_display_shortcuts() {
+        let mut osd = Xosd::new(3).unwrap();
+
+        assert_eq!(osd.display_string(0, 42), Ok(2));
+        assert_eq!(osd.display_percentage(1, 40), Ok(40));
+        assert_eq!(osd.display_slider(2, 60), Ok(60));
+        assert_eq!(
+            osd.snapshot().lines,
+            [
+                Some(Command::String("42".to_string())),
+                Some(Command::Percentage(40)),
+                Some(Command::Slider(60)),
+            ]
+        );
+
+        assert_eq!(
+            osd.display_percentage(1, 101),
+            Err(Error::OutOfRangePercentage)
+        );
+        assert_eq!(osd.display_slider(2, 101), Err(Error::OutOfRangePercentage));
+    }
+
+    #[test]
+    fn test_displayed_lines() {
+        let mut osd = Xosd::new(3).unwrap();
+        assert_eq!(osd.displayed_lines(), 0);
+
+        osd.display_string(0, "a").unwrap();
+        osd.display_percentage(2, 50).unwrap();
+        assert_eq!(osd.displayed_lines(), 2);
+
+        osd.display_string(0, "").unwrap();
+        assert_eq!(osd.displayed_lines(), 1);
+    }
+
+    #[test]
+    fn test_displayed_lines_scroll() {
+        let mut osd = Xosd::new(3).unwrap();
+        for line in 0..3 {
+            osd.display_string(line, line).unwrap();
+        }
+        assert_eq!(osd.displayed_lines(), 3);
+
+        osd.scroll(2).unwrap();
+        assert_eq!(osd.displayed_lines(), 1);
+
+        osd.display_string(1, "b").unwrap();
+        assert_eq!(osd.displayed_lines(), 2);
+
+        osd.scroll(3).unwrap();
+        assert_eq!(osd.displayed_lines(), 0);
+    }
+
+    #[test]
+    fn test_clear_line() {
+        let mut osd = Xosd::new(2).unwrap();
+        osd.set_text_transform(|text| format!("> {}", text));
+
+        osd.display_string(0, "a").unwrap();
+        osd.display_line_for(1, Command::string("b").unwrap(), Duration::from_secs(60))
+            .unwrap();
+
+        assert_eq!(osd.clear_line(1), Ok(()));
+        assert_eq!(osd.displayed_lines(), 1);
+        assert_eq!(osd.line_deadline(), None);
+
+        assert_eq!(osd.clear_all(), Ok(()));
+        assert_eq!(
+            osd.snapshot().lines,
+            [
+                Some(Command::String(String::new())),
+                Some(Command::String(String::new()))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clear_line_invalid() {
+        let mut osd = Xosd::new(2).unwrap();
+        osd.set_auto_grow(Some(4));
+
+        assert!(matches!(osd.clear_line(2), Err(Error::XosdError(_))));
+        assert_eq!(
+            osd.clear_line(-3),
+            Err(Error::InvalidLine { line: -3, max: 2 })
+        );
+        assert_eq!(osd.max_lines(), Ok(2));
+    }
+
+    #[test]
+    fn test_horizontal_align_into() {
+        let into = |align: HorizontalAlign| -> xosd_align { align.into() };
+
+        assert_eq!(into(HorizontalAlign::Left), xosd_align_XOSD_left);
+        assert_eq!(into(HorizontalAlign::Center), xosd_align_XOSD_center);
+        assert_eq!(into(HorizontalAlign::Right), xosd_align_XOSD_right);
+    }
+
+    #[test]
+    fn test_align_getters() {
+        let mut osd = Xosd::new(1).unwrap();
+        assert_eq!(osd.vertical_align(), VerticalAlign::default());
+        assert_eq!(osd.horizontal_align(), HorizontalAlign::default());
+
+        for align in &[
+            VerticalAlign::Top,
+            VerticalAlign::Center,
+            VerticalAlign::Bottom,
+        ] {
+            osd.set_vertical_align(*align).unwrap();
+            assert_eq!(osd.vertical_align(), *align);
+        }
+        for align in &[
+            HorizontalAlign::Left,
+            HorizontalAlign::Center,
+            HorizontalAlign::Right,
+        ] {
+            osd.set_horizontal_align(*align).unwrap();
+            assert_eq!(osd.horizontal_align(), *align);
+        }
+    }
+
+    #[test]
+    fn test_close() {
+        assert_eq!(Xosd::new(1).unwrap().close(), Ok(()));
+    }
+
+    #[test]
+    fn test_close_configured() {
+        let mut osd = Xosd::new(2).unwrap();
+        osd.set_text_transform(|text| text.to_uppercase());
+        osd.set_color("LimeGreen").unwrap();
+        osd.display_string(0, "Hello").unwrap();
+        osd.set_auto_grow(Some(4));
+        osd.display_string(3, "World").unwrap();
+
+        assert_eq!(osd.close(), Ok(()));
+    }
+
+    #[test]
+    fn test_set_no_timeout() {
+        let mut osd = Xosd::new(1).unwrap();
+        osd.set_timeout(1).unwrap();
+
+        assert_eq!(osd.set_no_timeout(), Ok(()));
+        assert_eq!(osd.snapshot().timeout, -1);
+
+        osd.display_string(0, "Always on top").unwrap();
+        thread::sleep(Duration::from_secs(2));
+        assert_eq!(osd.onscreen(), Ok(true));
+    }
+
+    #[test]
+    fn test_display_invalid_line() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        for line in &[-3, i32::MIN, 2, 3] {
+            assert_eq!(
+                osd.display_string(*line, "Hey"),
+                Err(Error::InvalidLine {
+                    line: *line,
+                    max: 2
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_invalid_line_auto_grow() {
+        let mut osd = Xosd::new(1).unwrap();
+        osd.set_auto_grow(Some(3));
+
+        assert_eq!(osd.display_string(2, "Hey"), Ok(3));
+        assert_eq!(
+            osd.display_string(3, "Hey"),
+            Err(Error::InvalidLine { line: 3, max: 3 })
+        );
+    }
+
+    #[test]
+    fn test_current_value() {
+        let mut osd = Xosd::new(2).unwrap();
+        assert_eq!(osd.current_value(0), None);
+
+        osd.display_percentage(0, 10).unwrap();
+        for _ in 0..3 {
+            let value = osd.current_value(0).unwrap();
+            osd.display_percentage(0, value + 10).unwrap();
+        }
+        assert_eq!(osd.current_value(0), Some(40));
+
+        osd.display_slider(1, 70).unwrap();
+        assert_eq!(osd.current_value(1), Some(70));
+        assert_eq!(osd.current_value(-1), Some(70));
+        assert_eq!(osd.current_value(-2), Some(40));
+        assert_eq!(osd.current_value(-3), None);
+
+        osd.scroll(1).unwrap();
+        assert_eq!(osd.current_value(0), Some(70));
+        assert_eq!(osd.current_value(1), None);
+
+        osd.display_string(0, "Muted").unwrap();
+        assert_eq!(osd.current_value(0), None);
+        assert_eq!(osd.current_value(-1), None);
+        assert_eq!(osd.current_value(2), None);
+    }
+
+    #[test]
+    fn test_command_value() {
+        assert_eq!(Command::Percentage(42).value(), Some(42));
+        assert_eq!(Command::Slider(7).value(), Some(7));
+        assert_eq!(Command::String(String::new()).value(), Some(0));
+        assert_eq!(Command::String("Grüße".to_string()).value(), Some(5));
+        assert_eq!(
+            Command::String("a".repeat(usize::from(u16::MAX) + 1)).value(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_command_value_display() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        for command in &[Command::Percentage(42), Command::string("value").unwrap()] {
+            assert_eq!(osd.display(0, command.clone()).ok(), command.value());
+        }
+
+        let command = Command::string("Grüße").unwrap();
+        assert_eq!(osd.display(0, command.clone()), Ok(7));
+        assert_eq!(command.value(), Some(5));
+    }
+
+    #[test]
+    fn test_command_display() {
+        assert_eq!(Command::Percentage(42).to_string(), "percentage: 42%");
+        assert_eq!(
+            Command::String("say \"hi\"".to_string()).to_string(),
+            r#"string: "say \"hi\"""#
+        );
+        assert_eq!(Command::Slider(42).to_string(), "slider: 42%");
+    }
 
     #[test]
-    #[ignore = "success depends on test order"]
-    fn test_error() {
-        assert_eq!(error_str().unwrap(), Cow::from(""))
+    fn test_display_lines() {
+        let mut osd = Xosd::new(3).unwrap();
+        let commands = ["a", "b", "c"]
+            .iter()
+            .map(|text| Command::string(text).unwrap());
+
+        assert_eq!(osd.display_lines(commands), Ok(()));
+        assert_eq!(
+            osd.snapshot().lines,
+            [
+                Some(Command::String("a".to_string())),
+                Some(Command::String("b".to_string())),
+                Some(Command::String("c".to_string())),
+            ]
+        );
     }
 
     #[test]
-    fn test_new() {
-        drop(Xosd::new(12).unwrap())
+    fn test_display_lines_partial() {
+        let mut osd = Xosd::new(3).unwrap();
+        osd.display_string(2, "kept").unwrap();
+
+        assert_eq!(osd.display_lines(vec![Command::Slider(20)]), Ok(()));
+        assert_eq!(
+            osd.snapshot().lines,
+            [
+                Some(Command::Slider(20)),
+                None,
+                Some(Command::String("kept".to_string())),
+            ]
+        );
     }
 
     #[test]
-    fn test_new_zero_line() {
-        assert_eq!(Xosd::new(0).err(), Some(Error::InvalidLineCount))
+    fn test_display_lines_too_many() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        assert_eq!(
+            osd.display_lines(vec![Command::Slider(20), Command::Slider(30)]),
+            Err(Error::TooManyLines { lines: 2, max: 1 })
+        );
+        assert_eq!(osd.snapshot().lines, [None]);
+    }
+
+    #[test]
+    fn test_fraction_percentage() {
+        assert_eq!(fraction_percentage(0.0), Ok(0));
+        assert_eq!(fraction_percentage(0.005), Ok(1));
+        assert_eq!(fraction_percentage(0.333), Ok(33));
+        assert_eq!(fraction_percentage(1.0), Ok(100));
+
+        for fraction in &[-0.1, 1.01, f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert_eq!(
+                fraction_percentage(*fraction),
+                Err(Error::OutOfRangePercentage)
+            );
+        }
+    }
+
+    #[test]
+    fn test_command_f32() {
+        assert_eq!(Command::percentage_f32(0.0), Ok(Command::Percentage(0)));
+        assert_eq!(Command::percentage_f32(0.005), Ok(Command::Percentage(1)));
+        assert_eq!(Command::percentage_f32(1.0), Ok(Command::Percentage(100)));
+
+        assert_eq!(Command::slider_f32(0.0), Ok(Command::Slider(0)));
+        assert_eq!(Command::slider_f32(0.005), Ok(Command::Slider(1)));
+        assert_eq!(Command::slider_f32(1.0), Ok(Command::Slider(100)));
+        assert_eq!(Command::slider_f32(-0.5), Err(Error::OutOfRangePercentage));
+    }
+
+    #[test]
+    fn test_command_range() {
+        assert_eq!(Command::percentage(0), Ok(Command::Percentage(0)));
+        assert_eq!(Command::percentage(100), Ok(Command::Percentage(100)));
+        assert_eq!(Command::percentage(101), Err(Error::OutOfRangePercentage));
+
+        assert_eq!(Command::slider(0), Ok(Command::Slider(0)));
+        assert_eq!(Command::slider(100), Ok(Command::Slider(100)));
+        assert_eq!(Command::slider(101), Err(Error::OutOfRangePercentage));
+    }
+
+    #[test]
+    fn test_display_empty_bar() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        assert_eq!(osd.display_percentage(0, 0), Ok(0));
+        assert_eq!(osd.display_slider(1, 0), Ok(0));
+        assert_eq!(osd.set_bar_length(Some(0)), Ok(()));
+        assert_eq!(
+            osd.set_bar_length(Some(101)),
+            Err(Error::OutOfRangePercentage)
+        );
+    }
+
+    #[test]
+    fn test_refresh() {
+        let displayed = Arc::new(Mutex::new(Vec::new()));
+        let mut osd = Xosd::new(3).unwrap();
+        {
+            let displayed = displayed.clone();
+            osd.set_text_transform(move |text| {
+                displayed.lock().unwrap().push(text.to_string());
+                format!("{}!", text)
+            });
+        }
+
+        osd.display_string(0, "a").unwrap();
+        osd.display_line_for(2, Command::Slider(30), Duration::from_secs(60))
+            .unwrap();
+        osd.hide().unwrap();
+
+        osd.set_color("White").unwrap();
+        assert_eq!(osd.refresh(), Ok(()));
+
+        assert_eq!(*displayed.lock().unwrap(), ["a"]);
+        assert_eq!(
+            osd.snapshot().lines,
+            [
+                Some(Command::String("a!".to_string())),
+                None,
+                Some(Command::Slider(30)),
+            ]
+        );
+        assert!(osd.line_deadline().is_some());
+        assert_eq!(osd.onscreen(), Ok(true));
+    }
+
+    #[test]
+    fn test_refresh_nothing_displayed() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        assert_eq!(osd.refresh(), Ok(()));
+        assert_eq!(osd.onscreen(), Ok(false));
+    }
+
+    #[test]
+    fn test_xosd_version() {
+        assert!(!XOSD_VERSION.is_empty());
+    }
+
+    #[test]
+    fn test_parse_align() {
+        for align in &[
+            VerticalAlign::Top,
+            VerticalAlign::Center,
+            VerticalAlign::Bottom,
+        ] {
+            assert_eq!(align.to_string().parse(), Ok(*align));
+        }
+        for align in &[
+            HorizontalAlign::Left,
+            HorizontalAlign::Center,
+            HorizontalAlign::Right,
+        ] {
+            assert_eq!(align.to_string().parse(), Ok(*align));
+        }
+
+        assert_eq!(VerticalAlign::try_from("CeNtEr"), Ok(VerticalAlign::Center));
+        assert_eq!(HorizontalAlign::try_from("LEFT"), Ok(HorizontalAlign::Left));
+    }
+
+    #[test]
+    fn test_parse_align_invalid() {
+        assert_eq!(
+            "left".parse::<VerticalAlign>(),
+            Err(Error::InvalidAlignment("left".to_string()))
+        );
+        assert_eq!(
+            HorizontalAlign::try_from(" right"),
+            Err(Error::InvalidAlignment(" right".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_set_position() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        assert_eq!(
+            osd.set_position(VerticalAlign::Bottom, HorizontalAlign::Right, 48, -10),
+            Ok(())
+        );
+        assert_eq!(osd.vertical_align(), VerticalAlign::Bottom);
+        assert_eq!(osd.horizontal_align(), HorizontalAlign::Right);
+
+        let snapshot = osd.snapshot();
+        assert_eq!(snapshot.vertical_offset, 48);
+        assert_eq!(snapshot.horizontal_offset, -10);
+    }
+
+    #[test]
+    fn test_validate_font() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        assert_eq!(osd.validate_font("fixed"), Ok(true));
+        assert_eq!(osd.snapshot().font, None);
+
+        osd.set_font("fixed").unwrap();
+        assert_eq!(
+            osd.validate_font("-misc-fixed-bold-r-normal--13-*-*-*-*-*-iso8859-1"),
+            Ok(true)
+        );
+        assert_eq!(osd.validate_font("-bogus-font-"), Ok(false));
+        assert_eq!(osd.snapshot().font.as_deref(), Some("fixed"));
+
+        assert_eq!(
+            osd.validate_font("fixed\n"),
+            Err(Error::InvalidFont("fixed\n".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_set_font_strict() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        assert_eq!(osd.set_font_strict("fixed"), Ok(()));
+        assert_eq!(
+            osd.set_font_strict("-bogus-font-"),
+            Err(Error::FontNotFound("-bogus-font-".to_string()))
+        );
+        assert_eq!(osd.snapshot().font.as_deref(), Some("fixed"));
+    }
+
+    #[test]
+    fn test_font() {
+        let mut osd = Xosd::new(1).unwrap();
+        assert_eq!(osd.font(), default_font());
+
+        osd.set_font("fixed").unwrap();
+        osd.set_font("-bogus-font-").unwrap_err();
+        assert_eq!(osd.font(), Ok(Cow::from("fixed")));
+    }
+
+    #[test]
+    fn test_scroll_up_and_down() {
+        let mut osd = Xosd::new(3).unwrap();
+        let text = |text: &str| Some(Command::String(text.to_string()));
+
+        osd.display_string(0, "a").unwrap();
+        osd.display_string(1, "b").unwrap();
+        osd.display_string(2, "c").unwrap();
+
+        osd.scroll_down(1).unwrap();
+        assert_eq!(osd.snapshot().lines, [None, text("a"), text("b")]);
+
+        osd.scroll(2).unwrap();
+        assert_eq!(osd.snapshot().lines, [text("b"), None, None]);
+
+        osd.scroll_down(3).unwrap();
+        assert_eq!(osd.snapshot().lines, [None, None, None]);
+        assert_eq!(osd.displayed_lines(), 0);
+
+        assert!(matches!(
+            osd.scroll_down(-1),
+            Err(Error::TryFromIntError(_))
+        ));
+    }
+
+    #[test]
+    fn test_display_blocking() {
+        let mut osd = Xosd::new(1).unwrap();
+        osd.set_timeout(1).unwrap();
+
+        osd.display_blocking(0, Command::string("Blocking").unwrap())
+            .unwrap();
+
+        assert_eq!(osd.onscreen(), Ok(false));
+    }
+
+    #[test]
+    fn test_wait_timeout_cleared() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.display_string(0, "Cleared").unwrap();
+        osd.hide().unwrap();
+
+        let start = Instant::now();
+        assert_eq!(
+            osd.wait_until_no_display_timeout(Duration::from_secs(5)),
+            Ok(true)
+        );
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_wait_timeout_elapsed() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.display_string(0, "Still here").unwrap();
+
+        let start = Instant::now();
+        assert_eq!(
+            osd.wait_until_no_display_timeout_with_interval(
+                Duration::from_millis(200),
+                Duration::from_millis(10)
+            ),
+            Ok(false)
+        );
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert_eq!(osd.onscreen(), Ok(true));
+    }
+
+    #[test]
+    fn test_wait_until_displayed() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        let start = Instant::now();
+        assert_eq!(
+            osd.wait_until_displayed(Duration::from_millis(200)),
+            Ok(false)
+        );
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        osd.display_string(0, "Shown").unwrap();
+
+        let start = Instant::now();
+        assert_eq!(osd.wait_until_displayed(Duration::from_secs(5)), Ok(true));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_wait_until_no_display_async() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.display_string(0, "Async").unwrap();
+        osd.hide().unwrap();
+
+        assert_eq!(osd.wait_until_no_display_async().await, Ok(()));
+        assert_eq!(osd.onscreen(), Ok(false));
+    }
+
+    #[test]
+    fn test_set_lines() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.set_color("LimeGreen").unwrap();
+        osd.set_shadow_offset(2).unwrap();
+        osd.display(0, Command::percentage(40).unwrap()).unwrap();
+
+        osd.set_lines(3).unwrap();
+        osd.display(2, Command::string("Track 3").unwrap()).unwrap();
+
+        assert_eq!(osd.max_lines(), Ok(3));
+        assert_eq!(osd.color(), Ok((50, 205, 50)));
+
+        let snapshot = osd.snapshot();
+        assert_eq!(snapshot.shadow_offset, 2);
+        assert_eq!(
+            snapshot.lines,
+            [
+                Some(Command::Percentage(40)),
+                None,
+                Some(Command::String("Track 3".to_string())),
+            ]
+        );
+
+        osd.set_lines(1).unwrap();
+        assert_eq!(osd.max_lines(), Ok(1));
+        assert_eq!(osd.snapshot().lines, [Some(Command::Percentage(40))]);
+
+        assert_eq!(osd.set_lines(0), Err(Error::InvalidLineCount));
+        assert_eq!(osd.set_lines(-1), Err(Error::InvalidLineCount));
+    }
+
+    #[test]
+    fn test_offset_getters() {
+        let mut osd = Xosd::new(1).unwrap();
+        assert_eq!(osd.shadow_offset(), 0);
+        assert_eq!(osd.outline_offset(), 0);
+
+        osd.set_shadow_offset(3).unwrap();
+        osd.set_outline_offset(2).unwrap();
+        assert_eq!(osd.shadow_offset(), 3);
+        assert_eq!(osd.outline_offset(), 2);
+    }
+
+    #[test]
+    fn test_presets() {
+        let osd = Xosd::notification().unwrap();
+        let snapshot = osd.snapshot();
+        assert_eq!(snapshot.lines.len(), 2);
+        assert_eq!(snapshot.color.as_deref(), Some("white"));
+        assert_eq!(snapshot.timeout, 3);
+        assert_eq!(snapshot.vertical_align, VerticalAlign::Top);
+        assert_eq!(snapshot.horizontal_align, HorizontalAlign::Center);
+
+        let osd = Xosd::volume_bar().unwrap();
+        let snapshot = osd.snapshot();
+        assert_eq!(snapshot.lines.len(), 1);
+        assert_eq!(snapshot.timeout, 1);
+        assert_eq!(snapshot.vertical_align, VerticalAlign::Bottom);
+        assert_eq!(snapshot.horizontal_align, HorizontalAlign::Center);
+    }
+
+    #[test]
+    fn test_scroll_checked() {
+        let mut osd = Xosd::new(4).unwrap();
+
+        osd.display_string(0, "a").unwrap();
+        osd.display_string(1, "b").unwrap();
+        osd.display_string(2, "c").unwrap();
+
+        assert_eq!(osd.scroll_checked(1), Ok(1));
+        assert_eq!(osd.scroll_checked(10), Ok(2));
+        assert_eq!(osd.scroll_checked(10), Ok(0));
+        assert_eq!(osd.displayed_lines(), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde_test::{assert_tokens, Token};
+
+        assert_tokens(
+            &VerticalAlign::Center,
+            &[Token::UnitVariant {
+                name: "VerticalAlign",
+                variant: "center",
+            }],
+        );
+        assert_tokens(
+            &HorizontalAlign::Right,
+            &[Token::UnitVariant {
+                name: "HorizontalAlign",
+                variant: "right",
+            }],
+        );
+        assert_tokens(
+            &Command::Percentage(42),
+            &[
+                Token::NewtypeVariant {
+                    name: "Command",
+                    variant: "percentage",
+                },
+                Token::U16(42),
+            ],
+        );
+        assert_tokens(
+            &Command::String("Hello".to_string()),
+            &[
+                Token::NewtypeVariant {
+                    name: "Command",
+                    variant: "string",
+                },
+                Token::Str("Hello"),
+            ],
+        );
+        assert_tokens(
+            &Command::Slider(7),
+            &[
+                Token::NewtypeVariant {
+                    name: "Command",
+                    variant: "slider",
+                },
+                Token::U16(7),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_display_countdown() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        let start = Instant::now();
+        osd.display_countdown(0, 50, Duration::from_millis(200))
+            .unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert_eq!(osd.current_value(0), Some(0));
+
+        assert_eq!(
+            osd.display_countdown(0, 101, Duration::from_millis(1)),
+            Err(Error::OutOfRangePercentage)
+        );
+        assert_eq!(
+            osd.display_countdown(1, 50, Duration::from_millis(1)),
+            Err(Error::InvalidLine { line: 1, max: 1 })
+        );
+
+        osd.display_countdown(-1, 0, Duration::from_millis(1))
+            .unwrap();
+        assert_eq!(osd.current_value(0), Some(0));
+        assert_eq!(
+            osd.display_countdown(-2, 50, Duration::from_millis(1)),
+            Err(Error::InvalidLine { line: -2, max: 1 })
+        );
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn test_error_null_message() {
+        let mut osd = Xosd::new(1).unwrap();
+        osd.show().unwrap();
+
+        assert_eq!(call_xosd!(xosd_show(osd.handle)), -1);
+        unsafe { xosd_error = std::ptr::null() };
+
+        assert_eq!(
+            last_error(),
+            Error::XosdError("[during show] unknown xosd error".to_string())
+        );
+    }
+
+    #[test]
+    fn test_line_content() {
+        let mut osd = Xosd::new(3).unwrap();
+        assert_eq!(osd.line_content(0), None);
+
+        osd.display_string(0, "First").unwrap();
+        osd.display_string(1, "Second").unwrap();
+        osd.display_percentage(2, 50).unwrap();
+        assert_eq!(osd.line_content(0), Some("First"));
+        assert_eq!(osd.line_content(1), Some("Second"));
+        assert_eq!(osd.line_content(2), None);
+        assert_eq!(osd.line_content(3), None);
+        assert_eq!(osd.line_content(-4), None);
+
+        osd.clear_line(0).unwrap();
+        assert_eq!(osd.line_content(0), None);
+
+        osd.scroll(1).unwrap();
+        assert_eq!(osd.line_content(0), Some("Second"));
+        assert_eq!(osd.line_content(1), None);
+        assert_eq!(osd.line_content(2), None);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn test_display_if_changed() {
+        let mut osd = Xosd::new(1).unwrap();
+        let displays = |osd: &Xosd| {
+            osd.mock_operations()
+                .iter()
+                .filter(|&&operation| operation == "xosd_display")
+                .count()
+        };
+
+        assert_eq!(
+            osd.display_if_changed(0, Command::string("Volume").unwrap()),
+            Ok(Some(6))
+        );
+        assert_eq!(
+            osd.display_if_changed(0, Command::string("Volume").unwrap()),
+            Ok(None)
+        );
+        assert_eq!(displays(&osd), 1);
+
+        osd.hide().unwrap();
+        assert_eq!(
+            osd.display_if_changed(0, Command::string("Volume").unwrap()),
+            Ok(Some(6))
+        );
+        assert_eq!(displays(&osd), 2);
+    }
+
+    #[test]
+    fn test_negative_line() {
+        let mut osd = Xosd::new(3).unwrap();
+
+        osd.display(-1, Command::string("Status").unwrap()).unwrap();
+        osd.display_string(-3, "Title").unwrap();
+        assert_eq!(osd.line_content(2), Some("Status"));
+        assert_eq!(osd.line_content(-1), Some("Status"));
+        assert_eq!(osd.line_content(0), Some("Title"));
+
+        osd.clear_line(-1).unwrap();
+        assert_eq!(osd.line_content(2), None);
+
+        assert_eq!(
+            osd.display(-4, Command::string("Nope").unwrap()),
+            Err(Error::InvalidLine { line: -4, max: 3 })
+        );
+        assert_eq!(
+            osd.clear_line(-4),
+            Err(Error::InvalidLine { line: -4, max: 3 })
+        );
+        assert_eq!(
+            osd.display(i32::MIN, Command::string("Nope").unwrap()),
+            Err(Error::InvalidLine {
+                line: i32::MIN,
+                max: 3
+            })
+        );
+        assert_eq!(osd.line_content(-4), None);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        osd.set_font("fixed").unwrap();
+        osd.set_color("LimeGreen").unwrap();
+        osd.set_shadow_color("white").unwrap();
+        osd.set_timeout(3).unwrap();
+        osd.set_bar_length(Some(40)).unwrap();
+        osd.set_position(VerticalAlign::Bottom, HorizontalAlign::Right, 10, 20)
+            .unwrap();
+        osd.set_shadow_offset(2).unwrap();
+        osd.display_string(0, "Kept").unwrap();
+
+        osd.reset().unwrap();
+
+        assert_eq!(osd.color(), Ok((0, 255, 0)));
+        assert_eq!(osd.font().unwrap(), default_font().unwrap());
+
+        let mut expected = Snapshot::new(2);
+        expected.lines[0] = Some(Command::String("Kept".to_string()));
+        expected.visible = true;
+        assert_eq!(osd.snapshot(), expected);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let mut osd = Xosd::new(1).unwrap();
+        assert!(matches!(osd.timeout(), Err(Error::TryFromIntError(_))));
+
+        osd.set_timeout(3).unwrap();
+        assert_eq!(osd.timeout(), Ok(3));
+
+        osd.set_timeout_duration(Duration::from_millis(1500))
+            .unwrap();
+        assert_eq!(osd.timeout(), Ok(2));
+
+        osd.set_no_timeout().unwrap();
+        assert!(osd.timeout().is_err());
+    }
+
+    #[test]
+    fn test_raw_round_trip() {
+        let osd = Xosd::new(3).unwrap();
+        let handle = osd.as_raw();
+        assert!(!handle.is_null());
+        std::mem::forget(osd);
+
+        let mut osd = unsafe { Xosd::from_raw(handle) };
+        assert_eq!(osd.as_raw(), handle);
+        assert_eq!(osd.max_lines(), Ok(3));
+        assert_eq!(osd.snapshot().lines.len(), 3);
+
+        osd.display_string(2, "Still works").unwrap();
+    }
+
+    #[test]
+    fn test_rgb_to_x11_round_trip() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        for &(red, green, blue) in &[(0, 0, 0), (50, 205, 50), (1, 128, 254), (255, 255, 255)] {
+            osd.set_color(rgb_to_x11(red, green, blue)).unwrap();
+
+            let (r, g, b) = osd.color().unwrap();
+            assert!(Color::rgb(r, g, b).approx_eq(&Color::rgb(red, green, blue), 1));
+        }
+    }
+
+    #[test]
+    fn test_with_overrides() {
+        let mut osd = Xosd::new(1).unwrap();
+        osd.set_color("white").unwrap();
+        osd.set_timeout(3).unwrap();
+        let before = osd.snapshot();
+
+        let value = osd
+            .with_overrides(
+                |osd| {
+                    osd.set_color("red")?;
+                    osd.set_font("fixed")?;
+                    osd.set_vertical_align(VerticalAlign::Center)
+                },
+                |osd| {
+                    assert_eq!(osd.color()?, (255, 0, 0));
+                    Ok(42)
+                },
+            )
+            .unwrap();
+
+        assert_eq!(value, 42);
+        assert_eq!(osd.color(), Ok((255, 255, 255)));
+        assert_eq!(osd.snapshot(), before);
+    }
+
+    #[test]
+    fn test_with_overrides_error() {
+        let mut osd = Xosd::new(1).unwrap();
+        let before = osd.snapshot();
+
+        let res: Result<()> = osd.with_overrides(
+            |osd| osd.set_shadow_offset(4),
+            |osd| osd.set_bar_length(Some(101)),
+        );
+
+        assert_eq!(res, Err(Error::OutOfRangePercentage));
+        assert_eq!(osd.shadow_offset(), 0);
+        assert_eq!(osd.color(), Ok((0, 255, 0)));
+        assert_eq!(osd.snapshot(), before);
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_log() {
+        use log::{Level, Log, Metadata, Record};
+
+        static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+        struct Capture;
+
+        impl Log for Capture {
+            fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+                metadata.level() <= Level::Debug
+            }
+
+            fn log(&self, record: &Record<'_>) {
+                RECORDS.lock().unwrap().push(record.args().to_string());
+            }
+
+            fn flush(&self) {}
+        }
+
+        static CAPTURE: Capture = Capture;
+        let _ = log::set_logger(&CAPTURE);
+        log::set_max_level(log::LevelFilter::Debug);
+
+        let mut osd = Xosd::new(1).unwrap();
+        osd.display_string(0, "Logged").unwrap();
+
+        let records = RECORDS.lock().unwrap();
+        assert!(records
+            .iter()
+            .any(|record| record == "display on line 0: string: \"Logged\""));
+        assert!(records
+            .iter()
+            .any(|record| record == "xosd_display returned 6"));
+    }
+
+    // Run on its own with `cargo test test_no_display -- --ignored`
+    #[cfg(not(feature = "mock"))]
+    #[test]
+    #[ignore = "removes DISPLAY for all tests running in parallel"]
+    fn test_no_display() {
+        let display = env::var_os("DISPLAY");
+        env::remove_var("DISPLAY");
+
+        let res = Xosd::new(1);
+
+        if let Some(display) = display {
+            env::set_var("DISPLAY", display);
+        }
+        assert_eq!(res.unwrap_err(), Error::NoDisplay);
+    }
+
+    #[test]
+    fn test_display_bar_colored() {
+        let mut osd = Xosd::new(2).unwrap();
+        let colors = Arc::new(Mutex::new(Vec::new()));
+        osd.set_color("white").unwrap();
+
+        let received = Arc::clone(&colors);
+        osd.set_on_color_change(move |color| received.lock().unwrap().push(color));
+
+        assert_eq!(
+            osd.display_bar_colored(1, Command::Percentage(15), "red"),
+            Ok(15)
+        );
+        assert_eq!(osd.color(), Ok((255, 255, 255)));
+        assert_eq!(osd.snapshot().color.as_deref(), Some("white"));
+        assert_eq!(osd.snapshot().lines[1], Some(Command::Percentage(15)));
+
+        assert_eq!(osd.display_bar_colored(0, Command::Slider(7), "red"), Ok(7));
+        assert_eq!(osd.snapshot().lines[0], Some(Command::Slider(7)));
+
+        assert_eq!(
+            osd.display_bar_colored(2, Command::Percentage(15), "red"),
+            Err(Error::InvalidLine { line: 2, max: 2 })
+        );
+        assert_eq!(osd.color(), Ok((255, 255, 255)));
+        assert_eq!(
+            osd.display_bar_colored(1, Command::String("15%".to_string()), "red"),
+            Err(Error::NotABar)
+        );
+        assert!(colors.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_display_bar_colored_default_color() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.display_bar_colored(0, Command::Percentage(50), "red")
+            .unwrap();
+        assert_eq!(osd.snapshot().color, None);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn test_display_bar_colored_operations() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.display_bar_colored(0, Command::Percentage(50), "red")
+            .unwrap();
+        assert_eq!(
+            osd.mock_operations(),
+            [
+                "xosd_create",
+                "xosd_set_colour",
+                "xosd_display",
+                "xosd_set_colour"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flash() {
+        let mut osd = Xosd::new(1).unwrap();
+
+        osd.flash(
+            0,
+            Command::string("Alert").unwrap(),
+            2,
+            Duration::from_millis(10),
+        )
+        .unwrap();
+
+        assert_eq!(osd.onscreen(), Ok(false));
+        assert_eq!(osd.line_content(0), Some("Alert"));
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn test_flash_operations() {
+        let mut osd = Xosd::new(1).unwrap();
+        let count = |osd: &Xosd, name: &str| {
+            osd.mock_operations()
+                .iter()
+                .filter(|&&operation| operation == name)
+                .count()
+        };
+
+        osd.flash(0, Command::Slider(50), 3, Duration::from_millis(1))
+            .unwrap();
+
+        assert_eq!(count(&osd, "xosd_display"), 1);
+        assert_eq!(count(&osd, "xosd_show"), 2);
+        assert_eq!(count(&osd, "xosd_hide"), 3);
+        assert_eq!(osd.onscreen(), Ok(false));
+
+        osd.flash(0, Command::Slider(50), 0, Duration::from_millis(1))
+            .unwrap();
+        assert_eq!(count(&osd, "xosd_display"), 1);
+    }
+
+    #[test]
+    fn test_onscreen_transitions() {
+        let mut osd = Xosd::new(1).unwrap();
+        assert_eq!(osd.onscreen(), Ok(false));
+
+        osd.display_string(0, "Visible").unwrap();
+        assert_eq!(osd.onscreen(), Ok(true));
+
+        for _ in 0..3 {
+            osd.hide().unwrap();
+            assert_eq!(osd.onscreen(), Ok(false));
+
+            osd.show().unwrap();
+            assert_eq!(osd.onscreen(), Ok(true));
+        }
     }
 }
@@ -0,0 +1,135 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    timer::{lock, Timer},
+    Command, Result, Xosd,
+};
+
+/// Clears lines of a shared [`Xosd`] in a background thread
+///
+/// Lines displayed with [`LineTimer::display_line_for`] are cleared by a
+/// background thread once their duration has passed, without calling
+/// [`Xosd::expire_lines`] yourself. Displaying something else on the line
+/// before that cancels the clear. The thread locks the [`Xosd`] only while
+/// clearing lines and is stopped when the [`LineTimer`] is dropped.
+///
+/// If clearing a line fails the error is logged as a warning with the `log`
+/// feature and printed to stderr without it. The remaining lines are cleared
+/// after the next call to [`LineTimer::display_line_for`].
+///
+/// # Example
+///
+/// ```
+/// # use std::{sync::{Arc, Mutex}, thread, time::Duration};
+/// # use xosd_rs::{Command, LineTimer, Xosd};
+/// let osd = Arc::new(Mutex::new(Xosd::new(2)?));
+/// let timer = LineTimer::new(Arc::clone(&osd));
+///
+/// osd.lock().unwrap().display(0, Command::string("Battery: 80%")?)?;
+/// timer.display_line_for(1, Command::string("Charger connected")?, Duration::from_millis(20))?;
+///
+/// thread::sleep(Duration::from_millis(100));
+///
+/// // The display shows:
+/// // Battery: 80%
+/// assert_eq!(osd.lock().unwrap().line_content(1), None);
+///
+/// # Ok::<(), xosd_rs::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct LineTimer {
+    osd: Arc<Mutex<Xosd>>,
+    timer: Timer,
+}
+
+impl LineTimer {
+    /// Start a background thread clearing the lines of `osd`
+    pub fn new(osd: Arc<Mutex<Xosd>>) -> Self {
+        let task_osd = Arc::clone(&osd);
+        let timer = Timer::spawn(move || {
+            let mut osd = lock(&task_osd);
+
+            match osd.expire_lines() {
+                Ok(()) => osd.line_deadline(),
+                Err(error) => {
+                    warn!("Could not clear xosd line: {}", error);
+                    None
+                }
+            }
+        });
+
+        Self { osd, timer }
+    }
+
+    /// Display data on a single line and clear it in the background after
+    /// `duration`
+    ///
+    /// See [`Xosd::display_line_for`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Xosd::display`].
+    pub fn display_line_for(&self, line: i32, command: Command, duration: Duration) -> Result<u16> {
+        let res = lock(&self.osd).display_line_for(line, command, duration)?;
+        self.timer.reschedule();
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_clear_in_background() {
+        let osd = Arc::new(Mutex::new(Xosd::new(3).unwrap()));
+        let timer = LineTimer::new(Arc::clone(&osd));
+
+        lock(&osd).display_string(0, "Status").unwrap();
+        timer
+            .display_line_for(
+                1,
+                Command::string("Toast").unwrap(),
+                Duration::from_millis(20),
+            )
+            .unwrap();
+        timer
+            .display_line_for(
+                -1,
+                Command::string("Later").unwrap(),
+                Duration::from_secs(5),
+            )
+            .unwrap();
+
+        thread::sleep(Duration::from_millis(200));
+
+        let osd = lock(&osd);
+        assert_eq!(osd.line_content(0), Some("Status"));
+        assert_eq!(osd.line_content(1), None);
+        assert_eq!(osd.line_content(2), Some("Later"));
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let osd = Arc::new(Mutex::new(Xosd::new(1).unwrap()));
+        let timer = LineTimer::new(Arc::clone(&osd));
+
+        timer
+            .display_line_for(
+                0,
+                Command::string("Toast").unwrap(),
+                Duration::from_millis(20),
+            )
+            .unwrap();
+        lock(&osd).display_string(0, "Status").unwrap();
+
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(lock(&osd).line_content(0), Some("Status"));
+    }
+}
@@ -0,0 +1,389 @@
+//! In-memory replacement for the xosd functions used with the `mock` feature
+//!
+//! Every function has the signature of its `xosd_sys` counterpart, so the
+//! rest of the crate does not know whether it talks to the real library. The
+//! types, constants and default settings are still taken from `xosd_sys`.
+
+#![allow(non_upper_case_globals)]
+
+use std::{
+    convert::TryFrom,
+    ffi::CStr,
+    os::raw::{c_char, c_int, c_uint},
+};
+
+use crate::{color::parse_x11_color, Xlfd, Xosd};
+
+pub(crate) use xosd_sys::{
+    osd_default_colour, osd_default_font, xosd, xosd_align, xosd_align_XOSD_center,
+    xosd_align_XOSD_left, xosd_align_XOSD_right, xosd_command, xosd_command_XOSD_percentage,
+    xosd_command_XOSD_slider, xosd_command_XOSD_string, xosd_pos, xosd_pos_XOSD_bottom,
+    xosd_pos_XOSD_middle, xosd_pos_XOSD_top,
+};
+
+/// The error message of the last failed mock function, like `xosd_error`
+pub(crate) static mut xosd_error: *const c_char = b"\0".as_ptr() as *const c_char;
+
+/// The color of a new window, xosd's default `green`
+const DEFAULT_COLOR: (c_int, c_int, c_int) = (0, 0xffff, 0);
+
+/// The state of a mocked XOSD window
+#[derive(Debug)]
+struct Window {
+    lines: Vec<Option<String>>,
+    color: (c_int, c_int, c_int),
+    mapped: bool,
+    operations: Vec<&'static str>,
+}
+
+/// Get the window behind `osd` and record `operation`
+unsafe fn window<'a>(osd: *mut xosd, operation: &'static str) -> &'a mut Window {
+    let window = &mut *(osd as *mut Window);
+    window.operations.push(operation);
+    window
+}
+
+/// Set the error message and return the error value of xosd
+fn fail(message: &'static [u8]) -> c_int {
+    unsafe { xosd_error = message.as_ptr() as *const c_char };
+    -1
+}
+
+fn parse_color(color: *const c_char) -> Option<(c_int, c_int, c_int)> {
+    let color = unsafe { CStr::from_ptr(color) }.to_str().ok()?;
+    let (red, green, blue) = parse_x11_color(color)?;
+
+    Some((red.into(), green.into(), blue.into()))
+}
+
+/// The last argument of `xosd_display`, which is variadic in C
+pub(crate) trait DisplayArg {
+    /// Returns the text stored on the line and the value `xosd_display`
+    /// returns
+    unsafe fn display(self) -> (Option<String>, c_int);
+}
+
+impl DisplayArg for c_uint {
+    unsafe fn display(self) -> (Option<String>, c_int) {
+        (None, self.min(100) as c_int)
+    }
+}
+
+impl DisplayArg for *const c_char {
+    unsafe fn display(self) -> (Option<String>, c_int) {
+        let text = CStr::from_ptr(self);
+
+        (
+            Some(text.to_string_lossy().into_owned()),
+            text.to_bytes().len() as c_int,
+        )
+    }
+}
+
+pub(crate) unsafe fn xosd_create(number_lines: c_int) -> *mut xosd {
+    if number_lines < 1 {
+        fail(b"Invalid number of lines\0");
+        return std::ptr::null_mut();
+    }
+
+    let window = Window {
+        lines: vec![None; number_lines as usize],
+        color: DEFAULT_COLOR,
+        mapped: false,
+        operations: vec!["xosd_create"],
+    };
+
+    Box::into_raw(Box::new(window)) as *mut xosd
+}
+
+pub(crate) unsafe fn xosd_uninit(osd: *mut xosd) -> c_int {
+    if osd.is_null() {
+        return fail(b"osd is null\0");
+    }
+
+    drop(Box::from_raw(osd as *mut Window));
+    0
+}
+
+pub(crate) unsafe fn xosd_display<T>(
+    osd: *mut xosd,
+    line: c_int,
+    _command: xosd_command,
+    arg: T,
+) -> c_int
+where
+    T: DisplayArg,
+{
+    let window = window(osd, "xosd_display");
+    let slot = match usize::try_from(line)
+        .ok()
+        .and_then(|line| window.lines.get_mut(line))
+    {
+        Some(slot) => slot,
+        None => return fail(b"xosd_display: Invalid Line Number\0"),
+    };
+
+    let (text, res) = arg.display();
+    *slot = text;
+    window.mapped = true;
+
+    res
+}
+
+pub(crate) unsafe fn xosd_is_onscreen(osd: *mut xosd) -> c_int {
+    window(osd, "xosd_is_onscreen").mapped.into()
+}
+
+pub(crate) unsafe fn xosd_wait_until_no_display(osd: *mut xosd) -> c_int {
+    window(osd, "xosd_wait_until_no_display").mapped = false;
+    0
+}
+
+pub(crate) unsafe fn xosd_hide(osd: *mut xosd) -> c_int {
+    let window = window(osd, "xosd_hide");
+    if !window.mapped {
+        return fail(b"xosd_hide: Already hidden\0");
+    }
+
+    window.mapped = false;
+    0
+}
+
+pub(crate) unsafe fn xosd_show(osd: *mut xosd) -> c_int {
+    let window = window(osd, "xosd_show");
+    if window.mapped {
+        return fail(b"xosd_show: Already shown\0");
+    }
+
+    window.mapped = true;
+    0
+}
+
+pub(crate) unsafe fn xosd_set_bar_length(osd: *mut xosd, _length: c_int) -> c_int {
+    window(osd, "xosd_set_bar_length");
+    0
+}
+
+pub(crate) unsafe fn xosd_set_pos(osd: *mut xosd, _pos: xosd_pos) -> c_int {
+    window(osd, "xosd_set_pos");
+    0
+}
+
+pub(crate) unsafe fn xosd_set_align(osd: *mut xosd, _align: xosd_align) -> c_int {
+    window(osd, "xosd_set_align");
+    0
+}
+
+pub(crate) unsafe fn xosd_set_shadow_offset(osd: *mut xosd, shadow_offset: c_int) -> c_int {
+    window(osd, "xosd_set_shadow_offset");
+    match shadow_offset < 0 {
+        true => fail(b"xosd_set_shadow_offset: Negative offset\0"),
+        false => 0,
+    }
+}
+
+pub(crate) unsafe fn xosd_set_outline_offset(osd: *mut xosd, outline_offset: c_int) -> c_int {
+    window(osd, "xosd_set_outline_offset");
+    match outline_offset < 0 {
+        true => fail(b"xosd_set_outline_offset: Negative offset\0"),
+        false => 0,
+    }
+}
+
+pub(crate) unsafe fn xosd_set_horizontal_offset(osd: *mut xosd, _offset: c_int) -> c_int {
+    window(osd, "xosd_set_horizontal_offset");
+    0
+}
+
+pub(crate) unsafe fn xosd_set_vertical_offset(osd: *mut xosd, _offset: c_int) -> c_int {
+    window(osd, "xosd_set_vertical_offset");
+    0
+}
+
+pub(crate) unsafe fn xosd_set_timeout(osd: *mut xosd, _timeout: c_int) -> c_int {
+    window(osd, "xosd_set_timeout");
+    0
+}
+
+pub(crate) unsafe fn xosd_set_colour(osd: *mut xosd, colour: *const c_char) -> c_int {
+    let window = window(osd, "xosd_set_colour");
+    match parse_color(colour) {
+        Some(color) => {
+            window.color = color;
+            0
+        }
+        None => fail(b"xosd_set_colour: Invalid colour\0"),
+    }
+}
+
+pub(crate) unsafe fn xosd_set_shadow_colour(osd: *mut xosd, colour: *const c_char) -> c_int {
+    window(osd, "xosd_set_shadow_colour");
+    match parse_color(colour) {
+        Some(_) => 0,
+        None => fail(b"xosd_set_shadow_colour: Invalid colour\0"),
+    }
+}
+
+pub(crate) unsafe fn xosd_set_outline_colour(osd: *mut xosd, colour: *const c_char) -> c_int {
+    window(osd, "xosd_set_outline_colour");
+    match parse_color(colour) {
+        Some(_) => 0,
+        None => fail(b"xosd_set_outline_colour: Invalid colour\0"),
+    }
+}
+
+/// Accepts `fixed` and every well-formed XLFD
+pub(crate) unsafe fn xosd_set_font(osd: *mut xosd, font: *const c_char) -> c_int {
+    window(osd, "xosd_set_font");
+    match CStr::from_ptr(font).to_str() {
+        Ok("fixed") => 0,
+        Ok(font) if font.parse::<Xlfd>().is_ok() => 0,
+        _ => fail(b"xosd_set_font: Could not load font\0"),
+    }
+}
+
+pub(crate) unsafe fn xosd_get_colour(
+    osd: *mut xosd,
+    red: *mut c_int,
+    green: *mut c_int,
+    blue: *mut c_int,
+) -> c_int {
+    let (r, g, b) = window(osd, "xosd_get_colour").color;
+    *red = r;
+    *green = g;
+    *blue = b;
+    0
+}
+
+pub(crate) unsafe fn xosd_scroll(osd: *mut xosd, lines: c_int) -> c_int {
+    let window = window(osd, "xosd_scroll");
+    let lines = match usize::try_from(lines) {
+        Ok(lines) if lines <= window.lines.len() => lines,
+        _ => return fail(b"xosd_scroll: Invalid number of lines\0"),
+    };
+
+    window.lines.drain(..lines);
+    window.lines.extend(std::iter::repeat_n(None, lines));
+    0
+}
+
+pub(crate) unsafe fn xosd_get_number_lines(osd: *mut xosd) -> c_int {
+    window(osd, "xosd_get_number_lines").lines.len() as c_int
+}
+
+/// Inspection of the mocked window, only available with the `mock` feature
+impl Xosd {
+    /// Get the text the mocked window shows on `line`
+    ///
+    /// Returns [`None`] if `line` is out of range, nothing was displayed on
+    /// it or it shows a percentage bar or slider.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(2)?;
+    ///
+    /// osd.display_string(0, "Hello")?;
+    /// osd.display_percentage(1, 50)?;
+    ///
+    /// assert_eq!(osd.mock_line(0), Some("Hello"));
+    /// assert_eq!(osd.mock_line(1), None);
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn mock_line(&self, line: i32) -> Option<&str> {
+        let window = unsafe { &*(self.handle as *const Window) };
+
+        window.lines.get(usize::try_from(line).ok()?)?.as_deref()
+    }
+
+    /// Get the xosd functions called on the mocked window in order
+    ///
+    /// The names are the ones of the C functions, e.g. `xosd_display`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use xosd_rs::Xosd;
+    /// let mut osd = Xosd::new(1)?;
+    ///
+    /// osd.set_timeout(3)?;
+    /// osd.display_string(0, "Hello")?;
+    ///
+    /// assert_eq!(
+    ///     osd.mock_operations(),
+    ///     ["xosd_create", "xosd_set_timeout", "xosd_display"]
+    /// );
+    ///
+    /// # Ok::<(), xosd_rs::Error>(())
+    /// ```
+    pub fn mock_operations(&self) -> &[&'static str] {
+        let window = unsafe { &*(self.handle as *const Window) };
+
+        &window.operations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Command, Error};
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let mut osd = Xosd::new(2).unwrap();
+
+        assert_eq!(osd.display_string(0, "Hello"), Ok(5));
+        assert_eq!(osd.display_slider(1, 30), Ok(30));
+        assert_eq!(osd.onscreen(), Ok(true));
+
+        assert_eq!(osd.mock_line(0), Some("Hello"));
+        assert_eq!(osd.mock_line(1), None);
+        assert_eq!(osd.mock_line(2), None);
+    }
+
+    #[test]
+    fn test_operations() {
+        let mut osd = Xosd::new(1).unwrap();
+        osd.display(0, Command::string("Hello").unwrap()).unwrap();
+        osd.hide().unwrap();
+        osd.hide().unwrap_err();
+
+        assert_eq!(
+            osd.mock_operations(),
+            ["xosd_create", "xosd_display", "xosd_hide", "xosd_hide"]
+        );
+    }
+
+    #[test]
+    fn test_color() {
+        let mut osd = Xosd::new(1).unwrap();
+        assert_eq!(osd.color(), Ok((0, 255, 0)));
+
+        osd.set_color("LimeGreen").unwrap();
+        assert_eq!(osd.color(), Ok((50, 205, 50)));
+
+        match osd.set_color("NoSuchColor") {
+            Err(Error::XosdError(message)) => {
+                assert_eq!(
+                    message,
+                    "[during set_colour] xosd_set_colour: Invalid colour"
+                )
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_scroll() {
+        let mut osd = Xosd::new(2).unwrap();
+        osd.display_string(0, "a").unwrap();
+        osd.display_string(1, "b").unwrap();
+
+        osd.scroll(1).unwrap();
+        assert_eq!(osd.mock_line(0), Some("b"));
+        assert_eq!(osd.mock_line(1), None);
+    }
+}
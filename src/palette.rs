@@ -0,0 +1,103 @@
+use crate::Color;
+
+/// The roles a color of a [`Palette`] can play
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Role {
+    /// Used for regular text
+    Foreground,
+
+    /// Used to highlight text
+    Accent,
+
+    /// Used for errors and warnings
+    Error,
+}
+
+/// A set of colors that look good together
+///
+/// Use [`Xosd::apply_palette_color`](crate::Xosd::apply_palette_color) to set
+/// the text color to one of the colors.
+///
+/// This type is only available with the `palettes` feature.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Palette {
+    pub foreground: Color,
+    pub accent: Color,
+    pub error: Color,
+}
+
+impl Palette {
+    /// The [Nord](https://www.nordtheme.com/) palette
+    pub const NORD: Palette = Palette {
+        foreground: Color::rgb(0xd8, 0xde, 0xe9),
+        accent: Color::rgb(0x88, 0xc0, 0xd0),
+        error: Color::rgb(0xbf, 0x61, 0x6a),
+    };
+
+    /// The [Solarized](https://ethanschoonover.com/solarized/) palette
+    pub const SOLARIZED: Palette = Palette {
+        foreground: Color::rgb(0x93, 0xa1, 0xa1),
+        accent: Color::rgb(0x26, 0x8b, 0xd2),
+        error: Color::rgb(0xdc, 0x32, 0x2f),
+    };
+
+    /// The [Gruvbox](https://github.com/morhetz/gruvbox) palette
+    pub const GRUVBOX: Palette = Palette {
+        foreground: Color::rgb(0xeb, 0xdb, 0xb2),
+        accent: Color::rgb(0xfa, 0xbd, 0x2f),
+        error: Color::rgb(0xfb, 0x49, 0x34),
+    };
+
+    /// Get the color for `role`
+    pub fn color(&self, role: Role) -> Color {
+        match role {
+            Role::Foreground => self.foreground,
+            Role::Accent => self.accent,
+            Role::Error => self.error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nord() {
+        assert_eq!(
+            Palette::NORD.color(Role::Foreground),
+            Color::rgb(216, 222, 233)
+        );
+        assert_eq!(Palette::NORD.color(Role::Accent), Color::rgb(136, 192, 208));
+        assert_eq!(Palette::NORD.color(Role::Error), Color::rgb(191, 97, 106));
+    }
+
+    #[test]
+    fn test_solarized() {
+        assert_eq!(
+            Palette::SOLARIZED.color(Role::Foreground),
+            Color::rgb(147, 161, 161)
+        );
+        assert_eq!(
+            Palette::SOLARIZED.color(Role::Accent),
+            Color::rgb(38, 139, 210)
+        );
+        assert_eq!(
+            Palette::SOLARIZED.color(Role::Error),
+            Color::rgb(220, 50, 47)
+        );
+    }
+
+    #[test]
+    fn test_gruvbox() {
+        assert_eq!(
+            Palette::GRUVBOX.color(Role::Foreground),
+            Color::rgb(235, 219, 178)
+        );
+        assert_eq!(
+            Palette::GRUVBOX.color(Role::Accent),
+            Color::rgb(250, 189, 47)
+        );
+        assert_eq!(Palette::GRUVBOX.color(Role::Error), Color::rgb(251, 73, 52));
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{Command, HorizontalAlign, VerticalAlign};
+
+/// The state of a XOSD window
+///
+/// The XOSD library can't report most of its settings, so [`Xosd`] keeps
+/// track of everything that was successfully set through it. A [`Snapshot`]
+/// is a copy of that state returned by [`Xosd::snapshot`]. It is meant for
+/// debugging and bug reports.
+///
+/// Settings that were never changed are [`None`] for strings and the xosd
+/// default otherwise.
+///
+/// [`Xosd`]: crate::Xosd
+/// [`Xosd::snapshot`]: crate::Xosd::snapshot
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    /// The data displayed on every line, [`None`] if nothing was displayed on
+    /// the line yet.
+    pub lines: Vec<Option<Command>>,
+
+    /// The text color
+    pub color: Option<String>,
+
+    /// The text font
+    pub font: Option<String>,
+
+    /// The shadow color
+    pub shadow_color: Option<String>,
+
+    /// The outline color
+    pub outline_color: Option<String>,
+
+    /// The timeout in seconds, `-1` if the window is never hidden
+    pub timeout: i32,
+
+    /// The length of the percentage bar or slider, [`None`] if xosd chooses
+    /// the length
+    pub bar_length: Option<u16>,
+
+    pub vertical_align: VerticalAlign,
+    pub horizontal_align: HorizontalAlign,
+    pub vertical_offset: i32,
+    pub horizontal_offset: i32,
+    pub shadow_offset: i32,
+    pub outline_offset: i32,
+
+    /// Whether the window was shown when the snapshot was taken
+    pub visible: bool,
+}
+
+impl Snapshot {
+    pub(crate) fn new(lines: usize) -> Self {
+        Self {
+            lines: vec![None; lines],
+            color: None,
+            font: None,
+            shadow_color: None,
+            outline_color: None,
+            timeout: -1,
+            bar_length: None,
+            vertical_align: VerticalAlign::default(),
+            horizontal_align: HorizontalAlign::default(),
+            vertical_offset: 0,
+            horizontal_offset: 0,
+            shadow_offset: 0,
+            outline_offset: 0,
+            visible: false,
+        }
+    }
+
+    /// Shift the lines up like `xosd_scroll` does
+    pub(crate) fn scroll(&mut self, lines: usize) {
+        let lines = lines.min(self.lines.len());
+
+        self.lines.drain(..lines);
+        self.lines.extend(std::iter::repeat_n(None, lines));
+    }
+
+    /// Shift the lines down, the opposite of [`Snapshot::scroll`]
+    pub(crate) fn scroll_down(&mut self, lines: usize) {
+        let max = self.lines.len();
+        let lines = lines.min(max);
+
+        self.lines.splice(..0, std::iter::repeat_n(None, lines));
+        self.lines.truncate(max);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> Option<Command> {
+        Some(Command::String(text.to_string()))
+    }
+
+    #[test]
+    fn test_new() {
+        let snapshot = Snapshot::new(2);
+
+        assert_eq!(snapshot.lines, [None, None]);
+        assert_eq!(snapshot.timeout, -1);
+        assert_eq!(snapshot.vertical_align, VerticalAlign::Top);
+    }
+
+    #[test]
+    fn test_scroll() {
+        let mut snapshot = Snapshot::new(3);
+        snapshot.lines = vec![text("a"), text("b"), text("c")];
+
+        snapshot.scroll(1);
+        assert_eq!(snapshot.lines, [text("b"), text("c"), None]);
+
+        snapshot.scroll(5);
+        assert_eq!(snapshot.lines, [None, None, None]);
+    }
+
+    #[test]
+    fn test_scroll_down() {
+        let mut snapshot = Snapshot::new(3);
+        snapshot.lines = vec![text("a"), text("b"), text("c")];
+
+        snapshot.scroll_down(1);
+        assert_eq!(snapshot.lines, [None, text("a"), text("b")]);
+
+        snapshot.scroll_down(5);
+        assert_eq!(snapshot.lines, [None, None, None]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde_test::{assert_tokens, Token};
+
+        let mut snapshot = Snapshot::new(2);
+        snapshot.lines[0] = text("Hi");
+        snapshot.color = Some("LimeGreen".to_string());
+        snapshot.timeout = 3;
+        snapshot.bar_length = Some(40);
+        snapshot.horizontal_align = HorizontalAlign::Right;
+        snapshot.vertical_offset = -20;
+        snapshot.visible = true;
+
+        assert_tokens(
+            &snapshot,
+            &[
+                Token::Struct {
+                    name: "Snapshot",
+                    len: 14,
+                },
+                Token::Str("lines"),
+                Token::Seq { len: Some(2) },
+                Token::Some,
+                Token::NewtypeVariant {
+                    name: "Command",
+                    variant: "string",
+                },
+                Token::Str("Hi"),
+                Token::None,
+                Token::SeqEnd,
+                Token::Str("color"),
+                Token::Some,
+                Token::Str("LimeGreen"),
+                Token::Str("font"),
+                Token::None,
+                Token::Str("shadow_color"),
+                Token::None,
+                Token::Str("outline_color"),
+                Token::None,
+                Token::Str("timeout"),
+                Token::I32(3),
+                Token::Str("bar_length"),
+                Token::Some,
+                Token::U16(40),
+                Token::Str("vertical_align"),
+                Token::UnitVariant {
+                    name: "VerticalAlign",
+                    variant: "top",
+                },
+                Token::Str("horizontal_align"),
+                Token::UnitVariant {
+                    name: "HorizontalAlign",
+                    variant: "right",
+                },
+                Token::Str("vertical_offset"),
+                Token::I32(-20),
+                Token::Str("horizontal_offset"),
+                Token::I32(0),
+                Token::Str("shadow_offset"),
+                Token::I32(0),
+                Token::Str("outline_offset"),
+                Token::I32(0),
+                Token::Str("visible"),
+                Token::Bool(true),
+                Token::StructEnd,
+            ],
+        );
+    }
+}
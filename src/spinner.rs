@@ -0,0 +1,74 @@
+use crate::{Command, Result, Xosd};
+
+/// The frames a [`Spinner`] cycles through
+const FRAMES: [&str; 4] = ["|", "/", "-", "\\"];
+
+/// A busy indicator for operations without a known progress
+///
+/// Every call to [`Spinner::tick`] displays the next frame of the cycle `|`,
+/// `/`, `-`, `\` on a line.
+///
+/// # Example
+///
+/// ```
+/// # use xosd_rs::{Spinner, Xosd};
+/// let mut osd = Xosd::new(1)?;
+/// let mut spinner = Spinner::new();
+///
+/// for _ in 0..8 {
+///     spinner.tick(&mut osd, 0)?;
+///     std::thread::sleep(std::time::Duration::from_millis(10));
+/// }
+///
+/// # Ok::<(), xosd_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Spinner {
+    frame: usize,
+}
+
+impl Spinner {
+    /// Create a new [`Spinner`] starting at the first frame
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current frame and advances to the next one
+    pub fn next_frame(&mut self) -> &'static str {
+        let frame = FRAMES[self.frame];
+        self.frame = (self.frame + 1) % FRAMES.len();
+        frame
+    }
+
+    /// Display the next frame on `line`
+    ///
+    /// # Errors
+    ///
+    /// * If `xosd_display` fails the xosd error message is wrapped in a
+    ///   [`Error::XosdError`](crate::Error::XosdError) and returned.
+    pub fn tick(&mut self, osd: &mut Xosd, line: i32) -> Result<u16> {
+        osd.display(line, Command::String(self.next_frame().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_frame() {
+        let mut spinner = Spinner::new();
+        let frames: Vec<_> = (0..6).map(|_| spinner.next_frame()).collect();
+
+        assert_eq!(frames, ["|", "/", "-", "\\", "|", "/"]);
+    }
+
+    #[test]
+    fn test_tick() {
+        let mut osd = Xosd::new(1).unwrap();
+        let mut spinner = Spinner::new();
+
+        assert_eq!(spinner.tick(&mut osd, 0), Ok(1));
+        assert_eq!(spinner.next_frame(), "/");
+    }
+}
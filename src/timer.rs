@@ -0,0 +1,156 @@
+use std::{
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
+/// A background thread that runs a task at the deadline the task returns
+///
+/// The task is run once when the thread starts and again whenever its last
+/// deadline has passed or [`Timer::reschedule`] was called. Returning
+/// [`None`] waits until the next [`Timer::reschedule`]. The thread is stopped
+/// and joined when the [`Timer`] is dropped.
+#[derive(Debug)]
+pub(crate) struct Timer {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    state: Mutex<State>,
+    wake: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Incremented by every [`Timer::reschedule`]
+    generation: u64,
+    stopped: bool,
+}
+
+impl Timer {
+    pub(crate) fn spawn<F>(mut task: F) -> Self
+    where
+        F: FnMut() -> Option<Instant> + Send + 'static,
+    {
+        let shared = Arc::new(Shared::default());
+        let thread_shared = Arc::clone(&shared);
+
+        let thread = thread::spawn(move || {
+            let shared = thread_shared;
+            let mut state = shared.lock();
+
+            while !state.stopped {
+                let generation = state.generation;
+                drop(state);
+
+                let deadline = task();
+
+                state = shared.lock();
+                while !state.stopped && state.generation == generation {
+                    state = match deadline {
+                        None => shared
+                            .wake
+                            .wait(state)
+                            .unwrap_or_else(PoisonError::into_inner),
+                        Some(deadline) => {
+                            let now = Instant::now();
+                            if now >= deadline {
+                                break;
+                            }
+
+                            shared
+                                .wake
+                                .wait_timeout(state, deadline - now)
+                                .unwrap_or_else(PoisonError::into_inner)
+                                .0
+                        }
+                    };
+                }
+            }
+        });
+
+        Self {
+            shared,
+            thread: Some(thread),
+        }
+    }
+
+    /// Run the task again, e.g. because its deadline changed
+    pub(crate) fn reschedule(&self) {
+        self.shared.lock().generation += 1;
+        self.shared.wake.notify_one();
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        self.shared.lock().stopped = true;
+        self.shared.wake.notify_one();
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Lock `mutex` even if another thread panicked while holding it
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_deadline() {
+        let runs = Arc::new(Mutex::new(Vec::new()));
+        let start = Instant::now();
+
+        let task_runs = Arc::clone(&runs);
+        let timer = Timer::spawn(move || {
+            let mut runs = lock(&task_runs);
+            runs.push(Instant::now());
+            if runs.len() < 2 {
+                Some(start + Duration::from_millis(20))
+            } else {
+                None
+            }
+        });
+
+        thread::sleep(Duration::from_millis(100));
+        drop(timer);
+
+        let runs = lock(&runs);
+        assert_eq!(runs.len(), 2);
+        assert!(runs[1] >= start + Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_reschedule() {
+        let runs = Arc::new(Mutex::new(0));
+
+        let task_runs = Arc::clone(&runs);
+        let timer = Timer::spawn(move || {
+            *lock(&task_runs) += 1;
+            None
+        });
+
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(*lock(&runs), 1);
+
+        timer.reschedule();
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(*lock(&runs), 2);
+    }
+}
@@ -0,0 +1,177 @@
+use std::{fmt, str::FromStr};
+
+use crate::Error;
+
+/// A X Logical Font Description
+///
+/// XLFDs are the font names understood by [`Xosd::set_font`]. They consist of
+/// 14 fields separated by dashes, e.g.
+/// `-misc-fixed-medium-r-normal--13-120-75-75-c-70-iso8859-1`. Every field
+/// may be a wildcard `*`.
+///
+/// Parse a XLFD, change some of its fields and turn it back into a string to
+/// tweak a font without counting dashes.
+///
+/// # Example
+///
+/// ```
+/// # use xosd_rs::{Xosd, Xlfd};
+/// let mut osd = Xosd::new(1)?;
+///
+/// let mut font: Xlfd = "-misc-fixed-medium-r-normal--*-*-*-*-*-*-iso8859-1".parse()?;
+/// font.weight = "bold".to_string();
+/// font.pixel_size = "13".to_string();
+///
+/// assert_eq!(
+///     font.to_string(),
+///     "-misc-fixed-bold-r-normal--13-*-*-*-*-*-iso8859-1"
+/// );
+///
+/// osd.set_font(font.to_string())?;
+///
+/// # Ok::<(), xosd_rs::Error>(())
+/// ```
+///
+/// [`Xosd::set_font`]: crate::Xosd::set_font
+#[derive(Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Xlfd {
+    pub foundry: String,
+    pub family: String,
+    pub weight: String,
+    pub slant: String,
+    pub set_width: String,
+    pub add_style: String,
+    pub pixel_size: String,
+    pub point_size: String,
+    pub resolution_x: String,
+    pub resolution_y: String,
+    pub spacing: String,
+    pub average_width: String,
+    pub registry: String,
+    pub encoding: String,
+}
+
+impl FromStr for Xlfd {
+    type Err = Error;
+
+    /// Parse a XLFD
+    ///
+    /// # Errors
+    ///
+    /// If `s` does not start with a dash or does not have exactly 14 fields
+    /// [`Error::InvalidFont`] is returned.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidFont(s.to_string());
+
+        let mut fields = s.strip_prefix('-').ok_or_else(invalid)?.split('-');
+        let mut next = || fields.next().map(str::to_string).ok_or_else(invalid);
+
+        let xlfd = Self {
+            foundry: next()?,
+            family: next()?,
+            weight: next()?,
+            slant: next()?,
+            set_width: next()?,
+            add_style: next()?,
+            pixel_size: next()?,
+            point_size: next()?,
+            resolution_x: next()?,
+            resolution_y: next()?,
+            spacing: next()?,
+            average_width: next()?,
+            registry: next()?,
+            encoding: next()?,
+        };
+
+        match fields.next() {
+            Some(_) => Err(invalid()),
+            None => Ok(xlfd),
+        }
+    }
+}
+
+impl fmt::Display for Xlfd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = [
+            &self.foundry,
+            &self.family,
+            &self.weight,
+            &self.slant,
+            &self.set_width,
+            &self.add_style,
+            &self.pixel_size,
+            &self.point_size,
+            &self.resolution_x,
+            &self.resolution_y,
+            &self.spacing,
+            &self.average_width,
+            &self.registry,
+            &self.encoding,
+        ];
+
+        for field in fields.iter() {
+            write!(f, "-{}", field)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXED: &str = "-misc-fixed-medium-r-normal--13-120-75-75-c-70-iso8859-1";
+
+    #[test]
+    fn test_parse() {
+        let xlfd: Xlfd = FIXED.parse().unwrap();
+
+        assert_eq!(xlfd.foundry, "misc");
+        assert_eq!(xlfd.family, "fixed");
+        assert_eq!(xlfd.weight, "medium");
+        assert_eq!(xlfd.slant, "r");
+        assert_eq!(xlfd.set_width, "normal");
+        assert_eq!(xlfd.add_style, "");
+        assert_eq!(xlfd.pixel_size, "13");
+        assert_eq!(xlfd.point_size, "120");
+        assert_eq!(xlfd.resolution_x, "75");
+        assert_eq!(xlfd.resolution_y, "75");
+        assert_eq!(xlfd.spacing, "c");
+        assert_eq!(xlfd.average_width, "70");
+        assert_eq!(xlfd.registry, "iso8859");
+        assert_eq!(xlfd.encoding, "1");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        assert_eq!(FIXED.parse::<Xlfd>().unwrap().to_string(), FIXED);
+    }
+
+    #[test]
+    fn test_change_pixel_size() {
+        let mut xlfd: Xlfd = FIXED.parse().unwrap();
+        xlfd.pixel_size = "20".to_string();
+        xlfd.point_size = "*".to_string();
+
+        assert_eq!(
+            xlfd.to_string(),
+            "-misc-fixed-medium-r-normal--20-*-75-75-c-70-iso8859-1"
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for font in &[
+            "fixed",
+            "misc-fixed-medium-r-normal--13-120-75-75-c-70-iso8859-1",
+            "-misc-fixed-medium-r-normal--13-120-75-75-c-70-iso8859",
+            "-misc-fixed-medium-r-normal--13-120-75-75-c-70-iso8859-1-extra",
+        ] {
+            assert_eq!(
+                font.parse::<Xlfd>(),
+                Err(Error::InvalidFont(font.to_string()))
+            );
+        }
+    }
+}
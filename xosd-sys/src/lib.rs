@@ -4,3 +4,9 @@
 #![doc(html_root_url = "https://docs.rs/xosd-sys/2.2.14")]
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+/// The version of xosd reported by `xosd-config --version` at build time
+///
+/// If `xosd-config` is not installed this is the version of xosd the bindings
+/// were written for.
+pub const XOSD_VERSION: &str = env!("XOSD_VERSION");